@@ -0,0 +1,126 @@
+// Copyright 2021 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Write;
+
+/// How keys and values are read from the command line and printed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// Printable ASCII as is, other bytes as `\xNN`.
+    Escaped,
+    /// Hexadecimal digits, optionally prefixed with `0x` on input.
+    Hex,
+    /// UTF-8 text; invalid sequences are replaced when printing.
+    Utf8,
+}
+
+impl Format {
+    pub const NAMES: &'static [&'static str] = &["escaped", "hex", "utf8"];
+
+    /// Returns the format called `name`, one of [`Format::NAMES`].
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "escaped" => Some(Format::Escaped),
+            "hex" => Some(Format::Hex),
+            "utf8" => Some(Format::Utf8),
+            _ => None,
+        }
+    }
+
+    pub fn parse(self, input: &str) -> Result<Vec<u8>, String> {
+        match self {
+            Format::Escaped => unescape(input),
+            Format::Hex => decode_hex(input),
+            Format::Utf8 => Ok(input.as_bytes().to_vec()),
+        }
+    }
+
+    pub fn display(self, bytes: &[u8]) -> String {
+        match self {
+            Format::Escaped => escape(bytes),
+            Format::Hex => encode_hex(bytes),
+            Format::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+        }
+    }
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(out, "{:02x}", byte);
+    }
+    out
+}
+
+pub fn decode_hex(input: &str) -> Result<Vec<u8>, String> {
+    let digits = input
+        .strip_prefix("0x")
+        .or_else(|| input.strip_prefix("0X"))
+        .unwrap_or(input);
+    digits
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .filter(|pair| pair.len() == 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| format!("Invalid hex digits in {:?}", input))
+        })
+        .collect()
+}
+
+fn escape(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len());
+    for &byte in bytes {
+        match byte {
+            b'\\' => out.push_str("\\\\"),
+            0x20..=0x7e => out.push(byte as char),
+            _ => {
+                let _ = write!(out, "\\x{:02x}", byte);
+            }
+        }
+    }
+    out
+}
+
+fn unescape(input: &str) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut bytes = input.bytes();
+    while let Some(byte) = bytes.next() {
+        if byte != b'\\' {
+            out.push(byte);
+            continue;
+        }
+        match bytes.next() {
+            Some(b'\\') => out.push(b'\\'),
+            Some(b'n') => out.push(b'\n'),
+            Some(b'r') => out.push(b'\r'),
+            Some(b't') => out.push(b'\t'),
+            Some(b'0') => out.push(0),
+            Some(b'x') => {
+                let high = bytes.next();
+                let low = bytes.next();
+                let digits = [high.unwrap_or(0), low.unwrap_or(0)];
+                let value = std::str::from_utf8(&digits)
+                    .ok()
+                    .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                    .ok_or_else(|| format!("Invalid \\x escape in {:?}", input))?;
+                out.push(value);
+            }
+            _ => return Err(format!("Invalid escape sequence in {:?}", input)),
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,647 @@
+// Copyright 2021 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An `ldb`-style tool to inspect and modify databases, built with the `cli`
+//! feature:
+//!
+//! ```text
+//! cargo run --features cli --bin rocksdb-ldb -- --db <path> scan --limit 10
+//! ```
+//!
+//! Reads can be made on a live database by opening it with `--read-only` or
+//! `--secondary <path>`.
+
+mod format;
+
+use std::collections::HashMap;
+use std::error;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::process;
+use std::sync::Arc;
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use rocksdb::{
+    backup::{BackupEngine, BackupEngineOptions, RestoreOptions},
+    checkpoint::Checkpoint,
+    AsColumnFamilyRef, BoundColumnFamily, ColumnFamily, DBRead, DBWithThreadMode, MultiThreaded,
+    Options, ReadOnlyDB, ReadOnlyDBWithThreadMode, ReadOptions, SecondaryDB,
+    SecondaryDBWithThreadMode, SingleThreaded, WriteBatch, WriteBatchIteratorCf, DB,
+};
+
+use format::{decode_hex, encode_hex, Format};
+
+type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
+
+/// Number of entries written per batch by `load`.
+const LOAD_BATCH_SIZE: usize = 1000;
+
+/// First line of the files written by `dump`.
+const DUMP_HEADER: &str = "# rocksdb-ldb dump v1";
+
+fn main() {
+    let matches = app().get_matches();
+    if let Err(e) = run(&matches) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn app() -> App<'static, 'static> {
+    let format_arg = |name: &'static str, help: &'static str| {
+        Arg::with_name(name)
+            .long(name)
+            .takes_value(true)
+            .possible_values(Format::NAMES)
+            .default_value("escaped")
+            .global(true)
+            .help(help)
+    };
+    let key = |name: &'static str, help: &'static str| {
+        Arg::with_name(name).long(name).takes_value(true).help(help)
+    };
+
+    App::new("rocksdb-ldb")
+        .about("Inspects and modifies RocksDB databases")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::with_name("db")
+                .long("db")
+                .takes_value(true)
+                .required(true)
+                .help("Path of the database"),
+        )
+        .arg(
+            Arg::with_name("read-only")
+                .long("read-only")
+                .global(true)
+                .help("Opens the database read-only"),
+        )
+        .arg(
+            Arg::with_name("secondary")
+                .long("secondary")
+                .takes_value(true)
+                .global(true)
+                .conflicts_with("read-only")
+                .help("Opens the database as a secondary keeping its files at this path"),
+        )
+        .arg(
+            Arg::with_name("cf")
+                .long("cf")
+                .takes_value(true)
+                .default_value("default")
+                .global(true)
+                .help("Column family to operate on"),
+        )
+        .arg(format_arg("key-format", "Format of keys"))
+        .arg(format_arg("value-format", "Format of values"))
+        .subcommand(SubCommand::with_name("list-cf").about("Lists the column families"))
+        .subcommand(
+            SubCommand::with_name("get")
+                .about("Prints the value of a key")
+                .arg(Arg::with_name("key").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("put")
+                .about("Sets the value of a key")
+                .arg(Arg::with_name("key").required(true))
+                .arg(Arg::with_name("value").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("delete")
+                .about("Deletes a key")
+                .arg(Arg::with_name("key").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("scan")
+                .about("Prints the entries of a key range")
+                .arg(key("from", "First key to print"))
+                .arg(key("to", "Key at which to stop, exclusive"))
+                .arg(key(
+                    "prefix",
+                    "Only prints the keys starting with this prefix",
+                ))
+                .arg(
+                    Arg::with_name("limit")
+                        .long("limit")
+                        .takes_value(true)
+                        .help("Maximum number of entries to print"),
+                )
+                .arg(
+                    Arg::with_name("keys-only")
+                        .long("keys-only")
+                        .help("Only prints the keys"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("dump")
+                .about("Writes the entries of the column family to a portable file")
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .help("File to write, standard output by default"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("load")
+                .about("Writes the entries of a file created by dump")
+                .arg(
+                    Arg::with_name("input")
+                        .long("input")
+                        .takes_value(true)
+                        .help("File to read, standard input by default"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("property")
+                .about("Prints a property of the column family")
+                .arg(Arg::with_name("name").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("compact")
+                .about("Compacts a key range of the column family")
+                .arg(key("from", "First key to compact"))
+                .arg(key("to", "Last key to compact")),
+        )
+        .subcommand(
+            SubCommand::with_name("checkpoint")
+                .about("Creates a checkpoint of the database")
+                .arg(Arg::with_name("dir").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("backup")
+                .about("Creates a new backup of the database")
+                .arg(
+                    Arg::with_name("backup-dir")
+                        .long("backup-dir")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("flush")
+                        .long("flush")
+                        .help("Flushes the memtables before the backup"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("restore")
+                .about("Restores the latest backup into the database path")
+                .arg(
+                    Arg::with_name("backup-dir")
+                        .long("backup-dir")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("wal-dir")
+                        .long("wal-dir")
+                        .takes_value(true)
+                        .help("Directory of the WAL files, the database path by default"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("wal-dump")
+                .about(
+                    "Prints the write batches in the WAL; opens the database read-write and \
+                     takes its lock",
+                )
+                .arg(
+                    Arg::with_name("since")
+                        .long("since")
+                        .takes_value(true)
+                        .default_value("0")
+                        .help("Sequence number to start from"),
+                ),
+        )
+}
+
+/// Settings shared by all commands.
+struct Context<'a> {
+    path: &'a str,
+    cf: &'a str,
+    key_format: Format,
+    value_format: Format,
+}
+
+impl<'a> Context<'a> {
+    fn new(matches: &'a ArgMatches<'a>, sub: &'a ArgMatches<'a>) -> Self {
+        // Global arguments are given either before or after the subcommand.
+        let value = |name| sub.value_of(name).or_else(|| matches.value_of(name));
+        let format = |name| value(name).and_then(Format::from_name).unwrap();
+        Self {
+            path: matches.value_of("db").unwrap(),
+            cf: value("cf").unwrap(),
+            key_format: format("key-format"),
+            value_format: format("value-format"),
+        }
+    }
+
+    fn key(&self, args: &ArgMatches, name: &str) -> Result<Option<Vec<u8>>> {
+        match args.value_of(name) {
+            Some(key) => Ok(Some(self.key_format.parse(key)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn required_key(&self, args: &ArgMatches, name: &str) -> Result<Vec<u8>> {
+        Ok(self.key(args, name)?.unwrap())
+    }
+}
+
+/// Calls a function generic over [`DBRead`] with the open database.
+macro_rules! with_db {
+    ($db:expr, $d:ident => $call:expr) => {
+        match $db {
+            Db::Primary($d) => $call,
+            Db::ReadOnly($d) => $call,
+            Db::Secondary($d) => $call,
+        }
+    };
+}
+
+fn run(matches: &ArgMatches) -> Result<()> {
+    let (command, sub) = matches.subcommand();
+    let sub = sub.unwrap();
+    let ctx = Context::new(matches, sub);
+
+    // These commands don't open the database.
+    match command {
+        "list-cf" => {
+            for name in DB::list_cf(&Options::default(), ctx.path)? {
+                println!("{}", name);
+            }
+            return Ok(());
+        }
+        "restore" => {
+            let mut engine = BackupEngine::open(
+                &BackupEngineOptions::default(),
+                sub.value_of("backup-dir").unwrap(),
+            )?;
+            let wal_dir = sub.value_of("wal-dir").unwrap_or(ctx.path);
+            engine.restore_from_latest_backup(ctx.path, wal_dir, &RestoreOptions::default())?;
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let db = Db::open(&ctx, matches, sub)?;
+    match command {
+        "get" => with_db!(&db, d => get(d, &ctx, sub)),
+        "scan" => with_db!(&db, d => scan(d, &ctx, sub)),
+        "dump" => with_db!(&db, d => dump(d, &ctx, sub)),
+        "property" => with_db!(&db, d => property(d, &ctx, sub)),
+        "put" => {
+            let db = db.primary()?;
+            let key = ctx.required_key(sub, "key")?;
+            let value = ctx.value_format.parse(sub.value_of("value").unwrap())?;
+            db.put_cf(&db.cf(ctx.cf)?, key, value)?;
+            Ok(())
+        }
+        "delete" => {
+            let db = db.primary()?;
+            db.delete_cf(&db.cf(ctx.cf)?, ctx.required_key(sub, "key")?)?;
+            Ok(())
+        }
+        "load" => load(db.primary()?, &ctx, sub),
+        "compact" => {
+            let db = db.primary()?;
+            let from = ctx.key(sub, "from")?;
+            let to = ctx.key(sub, "to")?;
+            db.compact_range_cf(&db.cf(ctx.cf)?, from, to);
+            Ok(())
+        }
+        "checkpoint" => {
+            let db = db.primary()?;
+            Checkpoint::new(db)?.create_checkpoint(sub.value_of("dir").unwrap())?;
+            Ok(())
+        }
+        "backup" => {
+            let db = db.primary()?;
+            let mut engine = BackupEngine::open(
+                &BackupEngineOptions::default(),
+                sub.value_of("backup-dir").unwrap(),
+            )?;
+            engine.create_new_backup_flush(db, sub.is_present("flush"))?;
+            Ok(())
+        }
+        "wal-dump" => wal_dump(db.primary()?, &ctx, sub),
+        _ => unreachable!("unknown command {}", command),
+    }
+}
+
+/// The database, opened as requested on the command line.
+enum Db {
+    Primary(DB),
+    ReadOnly(ReadOnlyDB),
+    Secondary(SecondaryDB),
+}
+
+impl Db {
+    fn open(ctx: &Context, matches: &ArgMatches, sub: &ArgMatches) -> Result<Db> {
+        let mut opts = Options::default();
+        let cfs = DB::list_cf(&opts, ctx.path)?;
+        let read_only = sub.is_present("read-only") || matches.is_present("read-only");
+        let secondary = sub
+            .value_of("secondary")
+            .or_else(|| matches.value_of("secondary"));
+
+        if read_only {
+            Ok(Db::ReadOnly(ReadOnlyDB::open_cf(
+                &opts, ctx.path, &cfs, false,
+            )?))
+        } else if let Some(secondary) = secondary {
+            opts.set_max_open_files(-1);
+            let db = SecondaryDB::open_cf(&opts, ctx.path, secondary, &cfs)?;
+            db.try_catch_up_with_primary()?;
+            Ok(Db::Secondary(db))
+        } else {
+            Ok(Db::Primary(DB::open_cf(&opts, ctx.path, &cfs)?))
+        }
+    }
+
+    fn primary(&self) -> Result<&DB> {
+        match self {
+            Db::Primary(db) => Ok(db),
+            _ => Err("this command needs the database opened read-write, \
+                      without --read-only or --secondary"
+                .into()),
+        }
+    }
+}
+
+/// Looks up column families by name in any kind of database.
+trait ColumnFamilies<'a> {
+    type Cf: AsColumnFamilyRef;
+
+    fn cf(&'a self, name: &str) -> Result<Self::Cf>;
+}
+
+macro_rules! impl_column_families {
+    ($($db:ident),*) => {
+        $(
+            impl<'a> ColumnFamilies<'a> for $db<SingleThreaded> {
+                type Cf = &'a ColumnFamily;
+
+                fn cf(&'a self, name: &str) -> Result<Self::Cf> {
+                    self.cf_handle(name).ok_or_else(|| cf_not_found(name))
+                }
+            }
+
+            impl<'a> ColumnFamilies<'a> for $db<MultiThreaded> {
+                type Cf = Arc<BoundColumnFamily<'a>>;
+
+                fn cf(&'a self, name: &str) -> Result<Self::Cf> {
+                    self.cf_handle(name).ok_or_else(|| cf_not_found(name))
+                }
+            }
+        )*
+    };
+}
+
+impl_column_families!(
+    DBWithThreadMode,
+    ReadOnlyDBWithThreadMode,
+    SecondaryDBWithThreadMode
+);
+
+fn cf_not_found(name: &str) -> Box<dyn error::Error> {
+    format!("column family {:?} not found", name).into()
+}
+
+fn get<'a, D: DBRead + ColumnFamilies<'a>>(
+    db: &'a D,
+    ctx: &Context,
+    args: &ArgMatches,
+) -> Result<()> {
+    match db.get_cf(&db.cf(ctx.cf)?, ctx.required_key(args, "key")?)? {
+        Some(value) => {
+            println!("{}", ctx.value_format.display(&value));
+            Ok(())
+        }
+        None => Err("key not found".into()),
+    }
+}
+
+fn scan<'a, D: DBRead + ColumnFamilies<'a>>(
+    db: &'a D,
+    ctx: &Context,
+    args: &ArgMatches,
+) -> Result<()> {
+    let from = ctx.key(args, "from")?;
+    let to = ctx.key(args, "to")?;
+    let prefix = ctx.key(args, "prefix")?;
+    let limit = match args.value_of("limit") {
+        Some(limit) => Some(limit.parse::<usize>()?),
+        None => None,
+    };
+    let keys_only = args.is_present("keys-only");
+
+    let mut readopts = ReadOptions::default();
+    if let Some(to) = to {
+        readopts.set_iterate_upper_bound(to);
+    }
+    let mut iter = db.raw_iterator_cf_opt(&db.cf(ctx.cf)?, readopts);
+    // Start at the later of `from` and `prefix`.
+    match (&from, &prefix) {
+        (Some(from), Some(prefix)) => iter.seek(from.max(prefix)),
+        (Some(start), None) | (None, Some(start)) => iter.seek(start),
+        (None, None) => iter.seek_to_first(),
+    }
+
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    let mut printed = 0;
+    while iter.valid() && limit != Some(printed) {
+        let key = iter.key().unwrap();
+        if let Some(prefix) = &prefix {
+            if !key.starts_with(prefix) {
+                break;
+            }
+        }
+        if keys_only {
+            writeln!(out, "{}", ctx.key_format.display(key))?;
+        } else {
+            writeln!(
+                out,
+                "{} : {}",
+                ctx.key_format.display(key),
+                ctx.value_format.display(iter.value().unwrap())
+            )?;
+        }
+        printed += 1;
+        iter.next();
+    }
+    iter.status()?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Writes every entry of the column family as a line of its hex encoded key
+/// and value, separated by a space.
+fn dump<'a, D: DBRead + ColumnFamilies<'a>>(
+    db: &'a D,
+    ctx: &Context,
+    args: &ArgMatches,
+) -> Result<()> {
+    let out: Box<dyn Write> = match args.value_of("output") {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+    let mut out = BufWriter::new(out);
+    writeln!(out, "{}", DUMP_HEADER)?;
+
+    let mut iter = db.raw_iterator_cf(&db.cf(ctx.cf)?);
+    iter.seek_to_first();
+    while iter.valid() {
+        let key = encode_hex(iter.key().unwrap());
+        let value = encode_hex(iter.value().unwrap());
+        writeln!(out, "{} {}", key, value)?;
+        iter.next();
+    }
+    iter.status()?;
+    out.flush()?;
+    Ok(())
+}
+
+fn load(db: &DB, ctx: &Context, args: &ArgMatches) -> Result<()> {
+    let input: Box<dyn BufRead> = match args.value_of("input") {
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+        None => Box::new(BufReader::new(io::stdin())),
+    };
+    let cf = &db.cf(ctx.cf)?;
+
+    let mut batch = WriteBatch::default();
+    for (number, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split(' ');
+        let (key, value) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(key), Some(value), None) => (decode_hex(key)?, decode_hex(value)?),
+            _ => return Err(format!("invalid entry on line {}", number + 1).into()),
+        };
+        batch.put_cf(cf, key, value);
+        if batch.len() >= LOAD_BATCH_SIZE {
+            db.write(std::mem::take(&mut batch))?;
+        }
+    }
+    if !batch.is_empty() {
+        db.write(batch)?;
+    }
+    Ok(())
+}
+
+fn property<'a, D: DBRead + ColumnFamilies<'a>>(
+    db: &'a D,
+    ctx: &Context,
+    args: &ArgMatches,
+) -> Result<()> {
+    let name = args.value_of("name").unwrap();
+    match db.property_value_cf(&db.cf(ctx.cf)?, name)? {
+        Some(value) => {
+            println!("{}", value);
+            Ok(())
+        }
+        None => Err(format!("unknown property {:?}", name).into()),
+    }
+}
+
+fn wal_dump(db: &DB, ctx: &Context, args: &ArgMatches) -> Result<()> {
+    let since = args.value_of("since").unwrap().parse::<u64>()?;
+    let mut cf_names = HashMap::new();
+    for name in DB::list_cf(&Options::default(), ctx.path)? {
+        let id = db.cf_id(&db.cf(&name)?)?;
+        cf_names.insert(id, name);
+    }
+    let stdout = io::stdout();
+    let mut printer = BatchPrinter {
+        cf_names,
+        ctx,
+        out: BufWriter::new(stdout.lock()),
+        sequence: 0,
+        error: None,
+    };
+    for (sequence, batch) in db.get_updates_since(since)? {
+        printer.sequence = sequence;
+        batch.iterate_cf(&mut printer)?;
+        if let Some(e) = printer.error.take() {
+            return Err(e);
+        }
+    }
+    printer.out.flush()?;
+    Ok(())
+}
+
+/// Prints the operations of write batches, one per line, with the sequence
+/// number of the batch and the name of the column family, or its id if it
+/// isn't open.
+struct BatchPrinter<'a, W: Write> {
+    cf_names: HashMap<u32, String>,
+    ctx: &'a Context<'a>,
+    out: W,
+    sequence: u64,
+    error: Option<Box<dyn error::Error>>,
+}
+
+impl<'a, W: Write> BatchPrinter<'a, W> {
+    fn print(&mut self, op: &str, cf_id: u32, key: &[u8], value: Option<String>) {
+        if let Err(e) = self.try_print(op, cf_id, key, value) {
+            self.error.get_or_insert(e);
+        }
+    }
+
+    fn try_print(&mut self, op: &str, cf_id: u32, key: &[u8], value: Option<String>) -> Result<()> {
+        let cf = match self.cf_names.get(&cf_id) {
+            Some(name) => name.clone(),
+            None => format!("#{}", cf_id),
+        };
+        let key = self.ctx.key_format.display(key);
+        match value {
+            Some(value) => writeln!(
+                self.out,
+                "{} {} cf={} {} : {}",
+                self.sequence, op, cf, key, value
+            )?,
+            None => writeln!(self.out, "{} {} cf={} {}", self.sequence, op, cf, key)?,
+        }
+        Ok(())
+    }
+}
+
+impl<'a, W: Write> WriteBatchIteratorCf for BatchPrinter<'a, W> {
+    fn put_cf(&mut self, cf_id: u32, key: &[u8], value: &[u8]) {
+        let value = self.ctx.value_format.display(value);
+        self.print("PUT", cf_id, key, Some(value));
+    }
+
+    fn merge_cf(&mut self, cf_id: u32, key: &[u8], value: &[u8]) {
+        let value = self.ctx.value_format.display(value);
+        self.print("MERGE", cf_id, key, Some(value));
+    }
+
+    fn delete_cf(&mut self, cf_id: u32, key: &[u8]) {
+        self.print("DELETE", cf_id, key, None);
+    }
+
+    fn single_delete_cf(&mut self, cf_id: u32, key: &[u8]) {
+        self.print("SINGLE_DELETE", cf_id, key, None);
+    }
+
+    fn delete_range_cf(&mut self, cf_id: u32, from: &[u8], to: &[u8]) {
+        let to = self.ctx.key_format.display(to);
+        self.print("DELETE_RANGE", cf_id, from, Some(to));
+    }
+}
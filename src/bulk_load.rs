@@ -0,0 +1,531 @@
+// Copyright 2021 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    db::{DBWithThreadMode, ThreadMode},
+    AsColumnFamilyRef, Error, IngestExternalFileOptions, Options, SstFileWriter,
+};
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicUsize};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A key/value pair buffered by a [`BulkLoader`].
+type Pair = (Vec<u8>, Vec<u8>);
+
+/// What a [`BulkLoader`] does when the same key is added more than once.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DuplicateKeyPolicy {
+    /// Keep the value added last.
+    LastWins,
+    /// Fail the load.
+    Error,
+}
+
+/// Options for a [`BulkLoader`].
+pub struct BulkLoadOptions {
+    memory_budget: usize,
+    target_file_size: u64,
+    threads: usize,
+    duplicate_key_policy: DuplicateKeyPolicy,
+    temp_dir: Option<PathBuf>,
+}
+
+impl BulkLoadOptions {
+    /// Sets the number of bytes of keys and values buffered in memory before
+    /// they are sorted and spilled to a temporary file.
+    ///
+    /// Writing the SST files needs up to `(threads + 1) * target_file_size`
+    /// bytes on top of this budget, see
+    /// [`set_target_file_size`](Self::set_target_file_size).
+    ///
+    /// Default: 64MB
+    pub fn set_memory_budget(&mut self, bytes: usize) {
+        self.memory_budget = bytes;
+    }
+
+    /// Sets the size of the key and value data written to each SST file.
+    /// Every writer thread buffers up to this amount of data, and so does the
+    /// thread handing the data out to them.
+    ///
+    /// Default: 64MB
+    pub fn set_target_file_size(&mut self, bytes: u64) {
+        self.target_file_size = bytes;
+    }
+
+    /// Sets the number of threads writing SST files.
+    ///
+    /// Default: 4
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Sets what happens when the same key is added more than once.
+    ///
+    /// Default: `DuplicateKeyPolicy::LastWins`
+    pub fn set_duplicate_key_policy(&mut self, policy: DuplicateKeyPolicy) {
+        self.duplicate_key_policy = policy;
+    }
+
+    /// Sets the directory in which the temporary directory for the sorted runs
+    /// and SST files is created. It should be on the same file system as the
+    /// database, so that ingestion can move the files instead of copying them.
+    ///
+    /// Default: the system temporary directory
+    pub fn set_temp_dir<P: AsRef<Path>>(&mut self, path: P) {
+        self.temp_dir = Some(path.as_ref().to_path_buf());
+    }
+}
+
+impl Default for BulkLoadOptions {
+    fn default() -> Self {
+        Self {
+            memory_budget: 64 * 1024 * 1024,
+            target_file_size: 64 * 1024 * 1024,
+            threads: 4,
+            duplicate_key_policy: DuplicateKeyPolicy::LastWins,
+            temp_dir: None,
+        }
+    }
+}
+
+/// Counters of a completed bulk load.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkLoadSummary {
+    /// Number of distinct keys written
+    pub keys: u64,
+    /// Number of values discarded in favour of a later value of the same key
+    pub duplicates: u64,
+    /// Number of SST files written
+    pub files: usize,
+}
+
+/// Loads unsorted key/value pairs into a column family through SST files.
+///
+/// Pairs are buffered in memory up to a budget, then sorted and spilled to
+/// temporary files. When the load is ingested, the sorted runs are merged and
+/// split into non-overlapping SST files, which are written in parallel with
+/// [`SstFileWriter`] and ingested at once. Keys are sorted bytewise, so the
+/// column family must use the default comparator.
+///
+/// Use one loader per column family.
+///
+/// ```
+/// use rocksdb::{BulkLoadOptions, BulkLoader, Options, DB};
+///
+/// let path = "_path_for_rocksdb_storage_bulk_load";
+/// {
+///     let db = DB::open_default(path).unwrap();
+///     let mut loader = BulkLoader::new(&Options::default(), &BulkLoadOptions::default()).unwrap();
+///     loader.put(b"k2", b"v2").unwrap();
+///     loader.put(b"k1", b"v1").unwrap();
+///     let summary = loader.ingest(&db).unwrap();
+///     assert_eq!(summary.keys, 2);
+///     assert_eq!(db.get(b"k1").unwrap().unwrap(), b"v1");
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+pub struct BulkLoader {
+    sst_options: Options,
+    memory_budget: usize,
+    target_file_size: u64,
+    threads: usize,
+    duplicate_key_policy: DuplicateKeyPolicy,
+    buffer: Vec<Pair>,
+    buffered_bytes: usize,
+    runs: Vec<PathBuf>,
+    duplicates: u64,
+    dir: TempDir,
+}
+
+impl BulkLoader {
+    /// Creates a loader writing SST files with `sst_options`, which should be
+    /// the options of the column family the files are ingested into.
+    pub fn new(sst_options: &Options, opts: &BulkLoadOptions) -> Result<Self, Error> {
+        let parent = opts.temp_dir.clone().unwrap_or_else(std::env::temp_dir);
+        Ok(Self {
+            sst_options: sst_options.clone(),
+            memory_budget: opts.memory_budget,
+            target_file_size: opts.target_file_size,
+            threads: opts.threads,
+            duplicate_key_policy: opts.duplicate_key_policy,
+            buffer: Vec::new(),
+            buffered_bytes: 0,
+            runs: Vec::new(),
+            duplicates: 0,
+            dir: TempDir::new(&parent)?,
+        })
+    }
+
+    /// Adds a key/value pair.
+    pub fn put<K, V>(&mut self, key: K, value: V) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let key = key.as_ref().to_vec();
+        let value = value.as_ref().to_vec();
+        self.buffered_bytes += key.len() + value.len();
+        self.buffer.push((key, value));
+        if self.buffered_bytes >= self.memory_budget {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Adds all key/value pairs of `pairs`.
+    pub fn extend<K, V, I>(&mut self, pairs: I) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        for (key, value) in pairs {
+            self.put(key, value)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the SST files and ingests them into the default column family,
+    /// moving the files instead of copying them when possible.
+    pub fn ingest<T: ThreadMode>(self, db: &DBWithThreadMode<T>) -> Result<BulkLoadSummary, Error> {
+        self.ingest_opts(db, &default_ingest_options())
+    }
+
+    /// Writes the SST files and ingests them into the default column family
+    /// with the given options.
+    pub fn ingest_opts<T: ThreadMode>(
+        mut self,
+        db: &DBWithThreadMode<T>,
+        opts: &IngestExternalFileOptions,
+    ) -> Result<BulkLoadSummary, Error> {
+        let (files, summary) = self.write_files()?;
+        if !files.is_empty() {
+            db.ingest_external_file_opts(opts, files)?;
+        }
+        Ok(summary)
+    }
+
+    /// Writes the SST files and ingests them into the column family, moving
+    /// the files instead of copying them when possible.
+    pub fn ingest_cf<T: ThreadMode>(
+        self,
+        db: &DBWithThreadMode<T>,
+        cf: &impl AsColumnFamilyRef,
+    ) -> Result<BulkLoadSummary, Error> {
+        self.ingest_cf_opts(db, cf, &default_ingest_options())
+    }
+
+    /// Writes the SST files and ingests them into the column family with the
+    /// given options.
+    pub fn ingest_cf_opts<T: ThreadMode>(
+        mut self,
+        db: &DBWithThreadMode<T>,
+        cf: &impl AsColumnFamilyRef,
+        opts: &IngestExternalFileOptions,
+    ) -> Result<BulkLoadSummary, Error> {
+        let (files, summary) = self.write_files()?;
+        if !files.is_empty() {
+            db.ingest_external_file_cf_opts(cf, opts, files)?;
+        }
+        Ok(summary)
+    }
+
+    /// Sorts the buffered pairs and writes them to a new run file.
+    fn spill(&mut self) -> Result<(), Error> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let pairs = self.take_sorted_buffer()?;
+        let path = self.dir.path.join(format!("{:06}.run", self.runs.len()));
+        let mut writer = BufWriter::new(File::create(&path).map_err(io_error)?);
+        for (key, value) in &pairs {
+            write_entry(&mut writer, key, value).map_err(io_error)?;
+        }
+        writer.flush().map_err(io_error)?;
+        self.runs.push(path);
+        Ok(())
+    }
+
+    /// Sorts the buffered pairs by key and resolves duplicate keys.
+    fn take_sorted_buffer(&mut self) -> Result<Vec<Pair>, Error> {
+        let mut pairs = std::mem::take(&mut self.buffer);
+        self.buffered_bytes = 0;
+        // The sort is stable, so pairs with the same key stay in insertion order.
+        pairs.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut deduped: Vec<Pair> = Vec::with_capacity(pairs.len());
+        for pair in pairs {
+            match deduped.last_mut() {
+                Some(last) if last.0 == pair.0 => {
+                    if self.duplicate_key_policy == DuplicateKeyPolicy::Error {
+                        return Err(duplicate_key_error(&pair.0));
+                    }
+                    self.duplicates += 1;
+                    *last = pair;
+                }
+                _ => deduped.push(pair),
+            }
+        }
+        Ok(deduped)
+    }
+
+    /// Merges the runs and writes them to SST files, in key order.
+    fn write_files(&mut self) -> Result<(Vec<PathBuf>, BulkLoadSummary), Error> {
+        let memory = self.take_sorted_buffer()?;
+        let mut runs = Vec::with_capacity(self.runs.len() + 1);
+        for path in &self.runs {
+            runs.push(Run::File(BufReader::new(
+                File::open(path).map_err(io_error)?,
+            )));
+        }
+        // The buffer holds the most recent pairs, so it is the last run.
+        runs.push(Run::Memory(memory.into_iter()));
+
+        // A rendezvous channel, so the only chunks in memory are the ones being
+        // written and the one being filled.
+        let (tx, rx) = mpsc::sync_channel::<(usize, Vec<Pair>)>(0);
+        let rx = Arc::new(Mutex::new(rx));
+        let workers: Vec<JoinHandle<Result<Vec<PathBuf>, Error>>> = (0..self.threads)
+            .map(|_| {
+                let rx = rx.clone();
+                let dir = self.dir.path.clone();
+                let sst_options = self.sst_options.clone();
+                thread::spawn(move || {
+                    let mut files = Vec::new();
+                    loop {
+                        let chunk = rx.lock().unwrap().recv();
+                        let (index, pairs) = match chunk {
+                            Ok(chunk) => chunk,
+                            Err(_) => return Ok(files),
+                        };
+                        // Chunks are numbered in key order, and so are the files.
+                        let path = dir.join(format!("{:06}.sst", index));
+                        let mut writer = SstFileWriter::create(&sst_options);
+                        writer.open(&path)?;
+                        for (key, value) in &pairs {
+                            writer.put(key, value)?;
+                        }
+                        writer.finish()?;
+                        files.push(path);
+                    }
+                })
+            })
+            .collect();
+
+        let merged = self.merge(runs, |index, pairs| tx.send((index, pairs)).is_ok());
+        drop(tx);
+
+        let mut files = Vec::new();
+        let mut worker_error = None;
+        for worker in workers {
+            match worker.join() {
+                Ok(Ok(written)) => files.extend(written),
+                Ok(Err(e)) => worker_error = Some(e),
+                Err(_) => {
+                    worker_error = Some(Error::new("A SST writer thread panicked".to_owned()));
+                }
+            }
+        }
+        let keys = merged?;
+        if let Some(e) = worker_error {
+            return Err(e);
+        }
+
+        files.sort();
+        let summary = BulkLoadSummary {
+            keys,
+            duplicates: self.duplicates,
+            files: files.len(),
+        };
+        Ok((files, summary))
+    }
+
+    /// Merges the sorted runs, resolving duplicate keys across them, and hands
+    /// chunks of about `target_file_size` bytes to `send`. Returns the number
+    /// of keys merged.
+    fn merge<F>(&mut self, mut runs: Vec<Run>, mut send: F) -> Result<u64, Error>
+    where
+        F: FnMut(usize, Vec<Pair>) -> bool,
+    {
+        let mut heap = BinaryHeap::new();
+        for (index, run) in runs.iter_mut().enumerate() {
+            if let Some((key, value)) = run.next()? {
+                heap.push(Reverse(HeapEntry { key, index, value }));
+            }
+        }
+
+        let mut keys = 0;
+        let mut chunk = Vec::new();
+        let mut chunk_bytes = 0;
+        let mut chunks = 0;
+        while let Some(Reverse(entry)) = heap.pop() {
+            if let Some((key, value)) = runs[entry.index].next()? {
+                heap.push(Reverse(HeapEntry {
+                    key,
+                    index: entry.index,
+                    value,
+                }));
+            }
+
+            // Entries with the same key pop in order of their run, the most
+            // recent first; the older ones are discarded.
+            let duplicate = match chunk.last() {
+                Some((key, _)) => *key == entry.key,
+                None => false,
+            };
+            if duplicate {
+                if self.duplicate_key_policy == DuplicateKeyPolicy::Error {
+                    return Err(duplicate_key_error(&entry.key));
+                }
+                self.duplicates += 1;
+                continue;
+            }
+
+            // Only cut a chunk once the next key differs from its last one.
+            if !chunk.is_empty() && chunk_bytes as u64 >= self.target_file_size {
+                if !send(chunks, std::mem::take(&mut chunk)) {
+                    break;
+                }
+                chunks += 1;
+                chunk_bytes = 0;
+            }
+
+            keys += 1;
+            chunk_bytes += entry.key.len() + entry.value.len();
+            chunk.push((entry.key, entry.value));
+        }
+        if !chunk.is_empty() {
+            send(chunks, chunk);
+        }
+        Ok(keys)
+    }
+}
+
+fn default_ingest_options() -> IngestExternalFileOptions {
+    let mut opts = IngestExternalFileOptions::default();
+    opts.set_move_files(true);
+    opts
+}
+
+fn duplicate_key_error(key: &[u8]) -> Error {
+    Error::new(format!("Duplicate key in bulk load: {:?}", key))
+}
+
+fn io_error(e: io::Error) -> Error {
+    Error::new(format!("Bulk load I/O error: {}", e))
+}
+
+fn write_entry<W: Write>(writer: &mut W, key: &[u8], value: &[u8]) -> io::Result<()> {
+    writer.write_all(&(key.len() as u64).to_le_bytes())?;
+    writer.write_all(&(value.len() as u64).to_le_bytes())?;
+    writer.write_all(key)?;
+    writer.write_all(value)
+}
+
+fn read_entry<R: Read>(reader: &mut R) -> io::Result<Option<Pair>> {
+    let mut len = [0; 8];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let key_len = u64::from_le_bytes(len) as usize;
+    reader.read_exact(&mut len)?;
+    let value_len = u64::from_le_bytes(len) as usize;
+    let mut key = vec![0; key_len];
+    reader.read_exact(&mut key)?;
+    let mut value = vec![0; value_len];
+    reader.read_exact(&mut value)?;
+    Ok(Some((key, value)))
+}
+
+/// A sorted run of pairs without duplicate keys.
+enum Run {
+    File(BufReader<File>),
+    Memory(std::vec::IntoIter<Pair>),
+}
+
+impl Run {
+    fn next(&mut self) -> Result<Option<Pair>, Error> {
+        match self {
+            Run::File(reader) => read_entry(reader).map_err(io_error),
+            Run::Memory(pairs) => Ok(pairs.next()),
+        }
+    }
+}
+
+/// Orders entries by key, then by descending run index so that the most
+/// recent value of a key is merged first.
+struct HeapEntry {
+    key: Vec<u8>,
+    index: usize,
+    value: Vec<u8>,
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key
+            .cmp(&other.key)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HeapEntry {}
+
+/// A uniquely named directory, removed with its content on drop.
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    fn new(parent: &Path) -> Result<Self, Error> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos());
+        let path = parent.join(format!(
+            "rocksdb-bulk-load-{}-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, atomic::Ordering::SeqCst),
+            nanos
+        ));
+        fs::create_dir_all(&path).map_err(io_error)?;
+        Ok(Self { path })
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
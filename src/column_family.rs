@@ -15,6 +15,7 @@
 use crate::{db::MultiThreaded, ffi, Options};
 
 use std::sync::Arc;
+use std::time::Duration;
 
 /// The name of the default column family.
 ///
@@ -28,19 +29,60 @@ pub const DEFAULT_COLUMN_FAMILY_NAME: &str = "default";
 pub struct ColumnFamilyDescriptor {
     pub(crate) name: String,
     pub(crate) options: Options,
+    pub(crate) ttl: ColumnFamilyTtl,
 }
 
 impl ColumnFamilyDescriptor {
     // Create a new column family descriptor with the specified name and options.
     pub fn new<S>(name: S, options: Options) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new_with_ttl(name, options, ColumnFamilyTtl::SameAsDb)
+    }
+
+    /// Creates a new column family descriptor with the specified name, options
+    /// and the TTL used when the database is opened with
+    /// [`open_cf_descriptors_with_ttl`](crate::DBWithThreadMode::open_cf_descriptors_with_ttl).
+    pub fn new_with_ttl<S>(name: S, options: Options, ttl: ColumnFamilyTtl) -> Self
     where
         S: Into<String>,
     {
         Self {
             name: name.into(),
             options,
+            ttl,
         }
     }
+
+    /// Returns the name of the column family.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Sets the TTL of the column family, see [`new_with_ttl`](Self::new_with_ttl).
+    pub fn set_ttl(&mut self, ttl: ColumnFamilyTtl) {
+        self.ttl = ttl;
+    }
+
+    /// Returns the TTL of the column family.
+    pub fn ttl(&self) -> ColumnFamilyTtl {
+        self.ttl
+    }
+}
+
+/// The Time to Live of a column family in a database opened with
+/// [`open_cf_descriptors_with_ttl`](crate::DBWithThreadMode::open_cf_descriptors_with_ttl).
+///
+/// Ignored when the database is opened without a TTL.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnFamilyTtl {
+    /// Use the TTL the database is opened with.
+    SameAsDb,
+    /// Never expire entries of this column family.
+    Disabled,
+    /// Expire entries of this column family after the given duration.
+    Enabled(Duration),
 }
 
 /// An opaque type used to represent a column family. Returned from some functions, and used
@@ -20,14 +20,16 @@ use crate::{
     db_options::OptionsMustOutliveDB,
     ffi,
     ffi_util::{from_cstr, opt_bytes_to_ptr, raw_data, to_cpath},
-    ColumnFamily, ColumnFamilyDescriptor, CompactOptions, DBIteratorWithThreadMode,
-    DBPinnableSlice, DBRawIteratorWithThreadMode, DBWALIterator, Direction, Error, FlushOptions,
-    IngestExternalFileOptions, IteratorMode, Options, ReadOptions, SnapshotWithThreadMode,
-    WriteBatch, WriteOptions, DEFAULT_COLUMN_FAMILY_NAME,
+    properties::{self, Property, PropertyType},
+    ColumnFamily, ColumnFamilyDescriptor, ColumnFamilyTtl, CompactOptions,
+    DBIteratorWithThreadMode, DBPinnableSlice, DBRawIteratorWithThreadMode, DBWALIterator,
+    Direction, Error, FlushOptions, IngestExternalFileOptions, IteratorMode, Options, ReadOptions,
+    SnapshotWithThreadMode, WriteBatch, WriteOptions, DEFAULT_COLUMN_FAMILY_NAME,
 };
 
 use libc::{self, c_char, c_int, c_uchar, c_void, size_t};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
 use std::ffi::{CStr, CString};
 use std::fmt;
 use std::fs;
@@ -208,7 +210,7 @@ unsafe impl<T: ThreadMode> Send for DBWithThreadMode<T> {}
 unsafe impl<T: ThreadMode> Sync for DBWithThreadMode<T> {}
 
 // Specifies whether open DB for read only.
-enum AccessType<'a> {
+pub(crate) enum AccessType<'a> {
     ReadWrite,
     ReadOnly { error_if_log_file_exist: bool },
     Secondary { secondary_path: &'a Path },
@@ -229,6 +231,9 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
     }
 
     /// Opens the database for read only with the specified options.
+    ///
+    /// See [`ReadOnlyDBWithThreadMode`](crate::ReadOnlyDBWithThreadMode) for a handle which
+    /// only exposes read operations.
     pub fn open_for_read_only<P: AsRef<Path>>(
         opts: &Options,
         path: P,
@@ -238,6 +243,9 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
     }
 
     /// Opens the database as a secondary.
+    ///
+    /// See [`SecondaryDBWithThreadMode`](crate::SecondaryDBWithThreadMode) for a handle which
+    /// only exposes the operations supported by secondary instances.
     pub fn open_as_secondary<P: AsRef<Path>>(
         opts: &Options,
         primary_path: P,
@@ -247,6 +255,8 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
     }
 
     /// Opens the database with a Time to Live compaction filter.
+    ///
+    /// Returns an error if `ttl` is longer than `c_int::MAX` seconds.
     pub fn open_with_ttl<P: AsRef<Path>>(
         opts: &Options,
         path: P,
@@ -278,6 +288,11 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
 
     /// Opens a database with the given database with a Time to Live compaction filter and
     /// column family descriptors.
+    ///
+    /// `ttl` applies to the column families whose descriptor TTL is
+    /// [`ColumnFamilyTtl::SameAsDb`], see [`ColumnFamilyDescriptor::new_with_ttl`].
+    /// TTLs are passed to RocksDB in seconds as a C `int`, so an error is
+    /// returned for TTLs longer than `c_int::MAX` seconds.
     pub fn open_cf_descriptors_with_ttl<P, I>(
         opts: &Options,
         path: P,
@@ -369,7 +384,7 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
     }
 
     /// Internal implementation for opening RocksDB.
-    fn open_cf_descriptors_internal<P, I>(
+    pub(crate) fn open_cf_descriptors_internal<P, I>(
         opts: &Options,
         path: P,
         cfs: I,
@@ -402,10 +417,10 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
             let mut cfs_v = cfs;
             // Always open the default column family.
             if !cfs_v.iter().any(|cf| cf.name == DEFAULT_COLUMN_FAMILY_NAME) {
-                cfs_v.push(ColumnFamilyDescriptor {
-                    name: String::from(DEFAULT_COLUMN_FAMILY_NAME),
-                    options: Options::default(),
-                });
+                cfs_v.push(ColumnFamilyDescriptor::new(
+                    DEFAULT_COLUMN_FAMILY_NAME,
+                    Options::default(),
+                ));
             }
             // We need to store our CStrings in an intermediate vector
             // so that their pointers remain valid.
@@ -485,7 +500,7 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
                 AccessType::WithTTL { ttl } => ffi_try!(ffi::rocksdb_open_with_ttl(
                     opts.inner,
                     cpath.as_ptr() as *const _,
-                    ttl.as_secs() as c_int,
+                    ttl_secs(ttl)?,
                 )),
             }
         };
@@ -534,6 +549,15 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
                     ))
                 }
                 AccessType::WithTTL { ttl } => {
+                    let ttls = cfs_v
+                        .iter()
+                        .map(|cf| match cf.ttl {
+                            ColumnFamilyTtl::SameAsDb => ttl_secs(ttl),
+                            ColumnFamilyTtl::Disabled => Ok(0),
+                            ColumnFamilyTtl::Enabled(ttl) => ttl_secs(ttl),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+
                     ffi_try!(ffi::rocksdb_open_column_families_with_ttl(
                         opts.inner,
                         cpath.as_ptr(),
@@ -541,7 +565,7 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
                         cfnames.as_ptr(),
                         cfopts.as_ptr(),
                         cfhandles.as_mut_ptr(),
-                        &(ttl.as_secs() as c_int) as *const _,
+                        ttls.as_ptr(),
                     ))
                 }
             }
@@ -776,6 +800,116 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
         self.get_pinned_cf_opt(cf, key, &ReadOptions::default())
     }
 
+    /// Copies the value associated with a key into `value`, reusing its allocation, and returns
+    /// whether the key was found. `value` is cleared first.
+    ///
+    /// This goes through [`get_pinned_opt`](Self::get_pinned_opt), so while the value isn't copied
+    /// into a new `Vec`, RocksDB still heap-allocates a pinnable slice handle on every call.
+    pub fn get_into_opt<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        readopts: &ReadOptions,
+        value: &mut Vec<u8>,
+    ) -> Result<bool, Error> {
+        value.clear();
+        self.get_with_opt(key, readopts, |v| value.extend_from_slice(v))
+            .map(|found| found.is_some())
+    }
+
+    /// Copies the value associated with a key into `value`, reusing its allocation, and returns
+    /// whether the key was found. Similar to get_into_opt but leverages default options, and
+    /// likewise still allocates a pinnable slice handle per call.
+    pub fn get_into<K: AsRef<[u8]>>(&self, key: K, value: &mut Vec<u8>) -> Result<bool, Error> {
+        self.get_into_opt(key, &ReadOptions::default(), value)
+    }
+
+    /// Copies the value associated with a key and the given column family into `value`, reusing
+    /// its allocation, and returns whether the key was found. `value` is cleared first.
+    pub fn get_into_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        readopts: &ReadOptions,
+        value: &mut Vec<u8>,
+    ) -> Result<bool, Error> {
+        value.clear();
+        self.get_with_cf_opt(cf, key, readopts, |v| value.extend_from_slice(v))
+            .map(|found| found.is_some())
+    }
+
+    /// Copies the value associated with a key and the given column family into `value`, reusing
+    /// its allocation. Similar to get_into_cf_opt but leverages default options.
+    pub fn get_into_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        value: &mut Vec<u8>,
+    ) -> Result<bool, Error> {
+        self.get_into_cf_opt(cf, key, &ReadOptions::default(), value)
+    }
+
+    /// Calls `f` with the value associated with a key, without copying it, and returns the result
+    /// of `f`, or `None` if the key wasn't found.
+    ///
+    /// The value is read through a [`DBPinnableSlice`], which RocksDB heap-allocates on every
+    /// call, so this avoids copying the value but isn't allocation-free.
+    pub fn get_with_opt<K, F, R>(
+        &self,
+        key: K,
+        readopts: &ReadOptions,
+        f: F,
+    ) -> Result<Option<R>, Error>
+    where
+        K: AsRef<[u8]>,
+        F: FnOnce(&[u8]) -> R,
+    {
+        Ok(self.get_pinned_opt(key, readopts)?.map(|value| f(&value)))
+    }
+
+    /// Calls `f` with the value associated with a key, without copying it. Similar to
+    /// get_with_opt but leverages default options, and likewise still allocates a pinnable slice
+    /// handle per call.
+    pub fn get_with<K, F, R>(&self, key: K, f: F) -> Result<Option<R>, Error>
+    where
+        K: AsRef<[u8]>,
+        F: FnOnce(&[u8]) -> R,
+    {
+        self.get_with_opt(key, &ReadOptions::default(), f)
+    }
+
+    /// Calls `f` with the value associated with a key and the given column family, without
+    /// copying it, and returns the result of `f`, or `None` if the key wasn't found.
+    pub fn get_with_cf_opt<K, F, R>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        readopts: &ReadOptions,
+        f: F,
+    ) -> Result<Option<R>, Error>
+    where
+        K: AsRef<[u8]>,
+        F: FnOnce(&[u8]) -> R,
+    {
+        Ok(self
+            .get_pinned_cf_opt(cf, key, readopts)?
+            .map(|value| f(&value)))
+    }
+
+    /// Calls `f` with the value associated with a key and the given column family, without
+    /// copying it. Similar to get_with_cf_opt but leverages default options.
+    pub fn get_with_cf<K, F, R>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        f: F,
+    ) -> Result<Option<R>, Error>
+    where
+        K: AsRef<[u8]>,
+        F: FnOnce(&[u8]) -> R,
+    {
+        self.get_with_cf_opt(cf, key, &ReadOptions::default(), f)
+    }
+
     /// Return the values associated with the given keys.
     pub fn multi_get<K, I>(&self, keys: I) -> Vec<Result<Option<Vec<u8>>, Error>>
     where
@@ -795,11 +929,143 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
         K: AsRef<[u8]>,
         I: IntoIterator<Item = K>,
     {
-        let (keys, keys_sizes): (Vec<Box<[u8]>>, Vec<_>) = keys
-            .into_iter()
-            .map(|k| (Box::from(k.as_ref()), k.as_ref().len()))
-            .unzip();
-        let ptr_keys: Vec<_> = keys.iter().map(|k| k.as_ptr() as *const c_char).collect();
+        let mut results = Vec::new();
+        self.multi_get_with_opt(keys, readopts, |_, value| {
+            results.push(value.map(|v| v.map(<[u8]>::to_vec)));
+        });
+        results
+    }
+
+    /// Return the values associated with the given keys and column families.
+    pub fn multi_get_cf<'a, 'b: 'a, K, I, W: 'b>(
+        &'a self,
+        keys: I,
+    ) -> Vec<Result<Option<Vec<u8>>, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = (&'b W, K)>,
+        W: AsColumnFamilyRef,
+    {
+        self.multi_get_cf_opt(keys, &ReadOptions::default())
+    }
+
+    /// Return the values associated with the given keys and column families using read options.
+    pub fn multi_get_cf_opt<'a, 'b: 'a, K, I, W: 'b>(
+        &'a self,
+        keys: I,
+        readopts: &ReadOptions,
+    ) -> Vec<Result<Option<Vec<u8>>, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = (&'b W, K)>,
+        W: AsColumnFamilyRef,
+    {
+        let mut results = Vec::new();
+        self.multi_get_with_cf_opt(keys, readopts, |_, value| {
+            results.push(value.map(|v| v.map(<[u8]>::to_vec)));
+        });
+        results
+    }
+
+    /// Copies the values associated with the given keys into `values`, reusing the allocations
+    /// of its elements, and returns whether each key was found.
+    ///
+    /// `values` is resized to the number of keys, and the value of the `i`-th key is written to
+    /// `values[i]`, which is left empty if the key wasn't found.
+    pub fn multi_get_into<K, I>(
+        &self,
+        keys: I,
+        values: &mut Vec<Vec<u8>>,
+    ) -> Vec<Result<bool, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        self.multi_get_into_opt(keys, &ReadOptions::default(), values)
+    }
+
+    /// Copies the values associated with the given keys into `values` using read options,
+    /// reusing the allocations of its elements, and returns whether each key was found.
+    pub fn multi_get_into_opt<K, I>(
+        &self,
+        keys: I,
+        readopts: &ReadOptions,
+        values: &mut Vec<Vec<u8>>,
+    ) -> Vec<Result<bool, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        let mut found = Vec::new();
+        self.multi_get_with_opt(keys, readopts, |i, value| {
+            found.push(copy_into(values, i, value));
+        });
+        values.truncate(found.len());
+        found
+    }
+
+    /// Copies the values associated with the given keys and column families into `values`,
+    /// reusing the allocations of its elements, and returns whether each key was found.
+    pub fn multi_get_into_cf<'a, 'b: 'a, K, I, W: 'b>(
+        &'a self,
+        keys: I,
+        values: &mut Vec<Vec<u8>>,
+    ) -> Vec<Result<bool, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = (&'b W, K)>,
+        W: AsColumnFamilyRef,
+    {
+        self.multi_get_into_cf_opt(keys, &ReadOptions::default(), values)
+    }
+
+    /// Copies the values associated with the given keys and column families into `values` using
+    /// read options, reusing the allocations of its elements, and returns whether each key was
+    /// found.
+    pub fn multi_get_into_cf_opt<'a, 'b: 'a, K, I, W: 'b>(
+        &'a self,
+        keys: I,
+        readopts: &ReadOptions,
+        values: &mut Vec<Vec<u8>>,
+    ) -> Vec<Result<bool, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = (&'b W, K)>,
+        W: AsColumnFamilyRef,
+    {
+        let mut found = Vec::new();
+        self.multi_get_with_cf_opt(keys, readopts, |i, value| {
+            found.push(copy_into(values, i, value));
+        });
+        values.truncate(found.len());
+        found
+    }
+
+    /// Calls `f` with the index and the value of each of the given keys, in order, without
+    /// copying the values.
+    pub fn multi_get_with<K, I, F>(&self, keys: I, f: F)
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+        F: FnMut(usize, Result<Option<&[u8]>, Error>),
+    {
+        self.multi_get_with_opt(keys, &ReadOptions::default(), f);
+    }
+
+    /// Calls `f` with the index and the value of each of the given keys using read options, in
+    /// order, without copying the values.
+    pub fn multi_get_with_opt<K, I, F>(&self, keys: I, readopts: &ReadOptions, f: F)
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+        F: FnMut(usize, Result<Option<&[u8]>, Error>),
+    {
+        let keys: Vec<K> = keys.into_iter().collect();
+        let ptr_keys: Vec<_> = keys
+            .iter()
+            .map(|k| k.as_ref().as_ptr() as *const c_char)
+            .collect();
+        let keys_sizes: Vec<_> = keys.iter().map(|k| k.as_ref().len()).collect();
 
         let mut values = vec![ptr::null_mut(); keys.len()];
         let mut values_sizes = vec![0_usize; keys.len()];
@@ -817,53 +1083,48 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
             );
         }
 
-        convert_values(values, values_sizes, errors)
+        for_each_value(values, values_sizes, errors, f);
     }
 
-    /// Return the values associated with the given keys and column families.
-    pub fn multi_get_cf<'a, 'b: 'a, K, I, W: 'b>(
-        &'a self,
-        keys: I,
-    ) -> Vec<Result<Option<Vec<u8>>, Error>>
+    /// Calls `f` with the index and the value of each of the given keys and column families, in
+    /// order, without copying the values.
+    pub fn multi_get_with_cf<'a, 'b: 'a, K, I, W: 'b, F>(&'a self, keys: I, f: F)
     where
         K: AsRef<[u8]>,
         I: IntoIterator<Item = (&'b W, K)>,
         W: AsColumnFamilyRef,
+        F: FnMut(usize, Result<Option<&[u8]>, Error>),
     {
-        self.multi_get_cf_opt(keys, &ReadOptions::default())
+        self.multi_get_with_cf_opt(keys, &ReadOptions::default(), f);
     }
 
-    /// Return the values associated with the given keys and column families using read options.
-    pub fn multi_get_cf_opt<'a, 'b: 'a, K, I, W: 'b>(
+    /// Calls `f` with the index and the value of each of the given keys and column families using
+    /// read options, in order, without copying the values.
+    pub fn multi_get_with_cf_opt<'a, 'b: 'a, K, I, W: 'b, F>(
         &'a self,
         keys: I,
         readopts: &ReadOptions,
-    ) -> Vec<Result<Option<Vec<u8>>, Error>>
-    where
+        f: F,
+    ) where
         K: AsRef<[u8]>,
         I: IntoIterator<Item = (&'b W, K)>,
         W: AsColumnFamilyRef,
+        F: FnMut(usize, Result<Option<&[u8]>, Error>),
     {
-        let mut boxed_keys: Vec<Box<[u8]>> = Vec::new();
-        let mut keys_sizes = Vec::new();
-        let mut column_families = Vec::new();
-        for (cf, key) in keys {
-            boxed_keys.push(Box::from(key.as_ref()));
-            keys_sizes.push(key.as_ref().len());
-            column_families.push(cf);
-        }
-        let ptr_keys: Vec<_> = boxed_keys
+        let (column_families, keys): (Vec<&W>, Vec<K>) = keys.into_iter().unzip();
+        let ptr_keys: Vec<_> = keys
             .iter()
-            .map(|k| k.as_ptr() as *const c_char)
+            .map(|k| k.as_ref().as_ptr() as *const c_char)
             .collect();
+        let keys_sizes: Vec<_> = keys.iter().map(|k| k.as_ref().len()).collect();
         let ptr_cfs: Vec<_> = column_families
             .iter()
             .map(|c| c.inner() as *const _)
             .collect();
 
-        let mut values = vec![ptr::null_mut(); boxed_keys.len()];
-        let mut values_sizes = vec![0_usize; boxed_keys.len()];
-        let mut errors = vec![ptr::null_mut(); boxed_keys.len()];
+        let mut values = vec![ptr::null_mut(); keys.len()];
+        let mut values_sizes = vec![0_usize; keys.len()];
+        let mut errors = vec![ptr::null_mut(); keys.len()];
         unsafe {
             ffi::rocksdb_multi_get_cf(
                 self.inner,
@@ -878,7 +1139,7 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
             );
         }
 
-        convert_values(values, values_sizes, errors)
+        for_each_value(values, values_sizes, errors, f);
     }
 
     /// Returns `false` if the given key definitely doesn't exist in the database, otherwise returns
@@ -1421,8 +1682,8 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
     ///
     /// Full list of properties could be find
     /// [here](https://github.com/facebook/rocksdb/blob/08809f5e6cd9cc4bc3958dd4d59457ae78c76660/include/rocksdb/db.h#L428-L634).
-    pub fn property_value(&self, name: &str) -> Result<Option<String>, Error> {
-        let prop_name = match CString::new(name) {
+    pub fn property_value(&self, name: impl AsRef<str>) -> Result<Option<String>, Error> {
+        let prop_name = match CString::new(name.as_ref()) {
             Ok(c) => c,
             Err(e) => {
                 return Err(Error::new(format!(
@@ -1460,9 +1721,9 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
     pub fn property_value_cf(
         &self,
         cf: &impl AsColumnFamilyRef,
-        name: &str,
+        name: impl AsRef<str>,
     ) -> Result<Option<String>, Error> {
-        let prop_name = match CString::new(name) {
+        let prop_name = match CString::new(name.as_ref()) {
             Ok(c) => c,
             Err(e) => {
                 return Err(Error::new(format!(
@@ -1497,7 +1758,7 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
     ///
     /// Full list of properties that return int values could be find
     /// [here](https://github.com/facebook/rocksdb/blob/08809f5e6cd9cc4bc3958dd4d59457ae78c76660/include/rocksdb/db.h#L654-L689).
-    pub fn property_int_value(&self, name: &str) -> Result<Option<u64>, Error> {
+    pub fn property_int_value(&self, name: impl AsRef<str>) -> Result<Option<u64>, Error> {
         match self.property_value(name) {
             Ok(Some(value)) => match value.parse::<u64>() {
                 Ok(int_value) => Ok(Some(int_value)),
@@ -1518,7 +1779,7 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
     pub fn property_int_value_cf(
         &self,
         cf: &impl AsColumnFamilyRef,
-        name: &str,
+        name: impl AsRef<str>,
     ) -> Result<Option<u64>, Error> {
         match self.property_value_cf(cf, name) {
             Ok(Some(value)) => match value.parse::<u64>() {
@@ -1533,6 +1794,100 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
         }
     }
 
+    /// Retrieves a RocksDB property made of `name=value` pairs, such as
+    /// [`properties::AGGREGATED_TABLE_PROPERTIES`](crate::properties::AGGREGATED_TABLE_PROPERTIES),
+    /// and splits it into a map.
+    pub fn property_map_value(
+        &self,
+        name: impl AsRef<str>,
+    ) -> Result<Option<HashMap<String, String>>, Error> {
+        Ok(self
+            .property_value(name)?
+            .map(|value| properties::parse_map(&value)))
+    }
+
+    /// Retrieves a RocksDB property made of `name=value` pairs for a specific
+    /// column family, and splits it into a map.
+    pub fn property_map_value_cf(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        name: impl AsRef<str>,
+    ) -> Result<Option<HashMap<String, String>>, Error> {
+        Ok(self
+            .property_value_cf(cf, name)?
+            .map(|value| properties::parse_map(&value)))
+    }
+
+    /// Retrieves an integer property, such as
+    /// [`properties::ESTIMATE_NUM_KEYS`](crate::properties::ESTIMATE_NUM_KEYS).
+    ///
+    /// Unlike [`property_int_value`](Self::property_int_value), only the
+    /// constants of [`properties`] are accepted, and properties which aren't
+    /// integers are rejected with an error.
+    pub fn int_property(&self, property: &Property) -> Result<Option<u64>, Error> {
+        property.expect_type(PropertyType::Int)?;
+        self.property_int_value(property)
+    }
+
+    /// Retrieves an integer property for a specific column family, see
+    /// [`int_property`](Self::int_property).
+    pub fn int_property_cf(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        property: &Property,
+    ) -> Result<Option<u64>, Error> {
+        property.expect_type(PropertyType::Int)?;
+        self.property_int_value_cf(cf, property)
+    }
+
+    /// Retrieves a free-form string property, such as
+    /// [`properties::LEVELSTATS`](crate::properties::LEVELSTATS).
+    ///
+    /// Unlike [`property_value`](Self::property_value), only the constants of
+    /// [`properties`] are accepted, and properties which aren't strings are
+    /// rejected with an error.
+    pub fn string_property(&self, property: &Property) -> Result<Option<String>, Error> {
+        property.expect_type(PropertyType::String)?;
+        self.property_value(property)
+    }
+
+    /// Retrieves a free-form string property for a specific column family, see
+    /// [`string_property`](Self::string_property).
+    pub fn string_property_cf(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        property: &Property,
+    ) -> Result<Option<String>, Error> {
+        property.expect_type(PropertyType::String)?;
+        self.property_value_cf(cf, property)
+    }
+
+    /// Retrieves a property made of `name=value` pairs, such as
+    /// [`properties::AGGREGATED_TABLE_PROPERTIES`](crate::properties::AGGREGATED_TABLE_PROPERTIES),
+    /// and splits it into a map.
+    ///
+    /// Unlike [`property_map_value`](Self::property_map_value), only the
+    /// constants of [`properties`] are accepted, and other properties are
+    /// rejected with an error.
+    pub fn map_property(
+        &self,
+        property: &Property,
+    ) -> Result<Option<HashMap<String, String>>, Error> {
+        property.expect_type(PropertyType::Map)?;
+        self.property_map_value(property)
+    }
+
+    /// Retrieves a property made of `name=value` pairs for a specific column
+    /// family, see [`map_property`](Self::map_property).
+    pub fn map_property_cf(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        property: &Property,
+    ) -> Result<Option<HashMap<String, String>>, Error> {
+        property.expect_type(PropertyType::Map)?;
+        self.property_map_value_cf(cf, property)
+    }
+
     /// The sequence number of the most recent transaction.
     pub fn latest_sequence_number(&self) -> u64 {
         unsafe { ffi::rocksdb_get_latest_sequence_number(self.inner) }
@@ -1700,6 +2055,65 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
         }
     }
 
+    /// Returns the approximate file system space used by keys in each of the
+    /// given ranges of the default column family.
+    pub fn get_approximate_sizes(&self, ranges: &[Range]) -> Result<Vec<u64>, Error> {
+        self.get_approximate_sizes_raw(None, ranges)
+    }
+
+    /// Returns the approximate file system space used by keys in each of the
+    /// given ranges of the column family.
+    pub fn get_approximate_sizes_cf(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        ranges: &[Range],
+    ) -> Result<Vec<u64>, Error> {
+        self.get_approximate_sizes_raw(Some(cf.inner()), ranges)
+    }
+
+    fn get_approximate_sizes_raw(
+        &self,
+        cf: Option<*mut ffi::rocksdb_column_family_handle_t>,
+        ranges: &[Range],
+    ) -> Result<Vec<u64>, Error> {
+        let start_keys: Vec<*const c_char> = ranges
+            .iter()
+            .map(|range| range.start_key.as_ptr() as *const c_char)
+            .collect();
+        let start_key_lens: Vec<size_t> =
+            ranges.iter().map(|range| range.start_key.len()).collect();
+        let end_keys: Vec<*const c_char> = ranges
+            .iter()
+            .map(|range| range.end_key.as_ptr() as *const c_char)
+            .collect();
+        let end_key_lens: Vec<size_t> = ranges.iter().map(|range| range.end_key.len()).collect();
+        let mut sizes: Vec<u64> = vec![0; ranges.len()];
+        unsafe {
+            match cf {
+                Some(cf) => ffi_try!(ffi::rocksdb_approximate_sizes_cf(
+                    self.inner,
+                    cf,
+                    ranges.len() as c_int,
+                    start_keys.as_ptr(),
+                    start_key_lens.as_ptr(),
+                    end_keys.as_ptr(),
+                    end_key_lens.as_ptr(),
+                    sizes.as_mut_ptr(),
+                )),
+                None => ffi_try!(ffi::rocksdb_approximate_sizes(
+                    self.inner,
+                    ranges.len() as c_int,
+                    start_keys.as_ptr(),
+                    start_key_lens.as_ptr(),
+                    end_keys.as_ptr(),
+                    end_key_lens.as_ptr(),
+                    sizes.as_mut_ptr(),
+                )),
+            }
+        }
+        Ok(sizes)
+    }
+
     /// Delete sst files whose keys are entirely in the given range.
     ///
     /// Could leave some keys in the range which are in files which are not
@@ -1745,6 +2159,14 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
         }
     }
 
+    /// Returns the ID RocksDB assigned to the given column family.
+    ///
+    /// These IDs are reported by [`WriteBatch::iterate_cf`] and stay the same
+    /// across reopens of the database.
+    pub fn cf_id(&self, cf: &impl AsColumnFamilyRef) -> Result<u32, Error> {
+        WriteBatch::column_family_id(cf)
+    }
+
     /// Request stopping background work, if wait is true wait until it's done.
     pub fn cancel_all_background_work(&self, wait: bool) {
         unsafe {
@@ -1791,6 +2213,25 @@ impl DBWithThreadMode<SingleThreaded> {
     pub fn cf_handle(&self, name: &str) -> Option<&ColumnFamily> {
         self.cfs.cfs.get(name)
     }
+
+    /// Returns the names of the open column families.
+    #[cfg(feature = "metrics")]
+    pub(crate) fn cf_names(&self) -> Vec<String> {
+        self.cfs.cfs.keys().cloned().collect()
+    }
+
+    /// Returns the name of the open column family with the given ID
+    pub fn cf_name_by_id(&self, id: u32) -> Result<Option<String>, Error> {
+        if id == 0 {
+            return Ok(Some(DEFAULT_COLUMN_FAMILY_NAME.to_owned()));
+        }
+        for (name, cf) in &self.cfs.cfs {
+            if WriteBatch::column_family_id(cf)? == id {
+                return Ok(Some(name.clone()));
+            }
+        }
+        Ok(None)
+    }
 }
 
 impl DBWithThreadMode<MultiThreaded> {
@@ -1814,6 +2255,12 @@ impl DBWithThreadMode<MultiThreaded> {
         }
     }
 
+    /// Returns the names of the open column families.
+    #[cfg(feature = "metrics")]
+    pub(crate) fn cf_names(&self) -> Vec<String> {
+        self.cfs.cfs.read().unwrap().keys().cloned().collect()
+    }
+
     /// Returns the underlying column family handle
     pub fn cf_handle(&self, name: &str) -> Option<Arc<BoundColumnFamily>> {
         self.cfs
@@ -1824,6 +2271,19 @@ impl DBWithThreadMode<MultiThreaded> {
             .cloned()
             .map(UnboundColumnFamily::bound_column_family)
     }
+
+    /// Returns the name of the open column family with the given ID
+    pub fn cf_name_by_id(&self, id: u32) -> Result<Option<String>, Error> {
+        if id == 0 {
+            return Ok(Some(DEFAULT_COLUMN_FAMILY_NAME.to_owned()));
+        }
+        for (name, cf) in self.cfs.cfs.read().unwrap().iter() {
+            if WriteBatch::column_family_id(&cf.clone().bound_column_family())? == id {
+                return Ok(Some(name.clone()));
+            }
+        }
+        Ok(None)
+    }
 }
 
 impl<T: ThreadMode> Drop for DBWithThreadMode<T> {
@@ -1841,6 +2301,21 @@ impl<T: ThreadMode> fmt::Debug for DBWithThreadMode<T> {
     }
 }
 
+/// A range of keys, `start_key` is included, but not `end_key`.
+///
+/// You should make sure `end_key` is not less than `start_key`.
+pub struct Range<'a> {
+    start_key: &'a [u8],
+    end_key: &'a [u8],
+}
+
+impl<'a> Range<'a> {
+    /// Creates the range of keys from `start_key` up to, but excluding, `end_key`.
+    pub fn new(start_key: &'a [u8], end_key: &'a [u8]) -> Range<'a> {
+        Range { start_key, end_key }
+    }
+}
+
 /// The metadata that describes a SST file
 #[derive(Debug, Clone)]
 pub struct LiveFile {
@@ -1860,6 +2335,17 @@ pub struct LiveFile {
     pub num_deletions: u64,
 }
 
+/// Converts a TTL into the seconds RocksDB takes as a C `int`.
+fn ttl_secs(ttl: Duration) -> Result<c_int, Error> {
+    c_int::try_from(ttl.as_secs()).map_err(|_| {
+        Error::new(format!(
+            "TTL of {} seconds exceeds the maximum of {} seconds",
+            ttl.as_secs(),
+            c_int::MAX
+        ))
+    })
+}
+
 fn convert_options(opts: &[(&str, &str)]) -> Result<Vec<(CString, CString)>, Error> {
     opts.iter()
         .map(|(name, value)| {
@@ -1876,25 +2362,51 @@ fn convert_options(opts: &[(&str, &str)]) -> Result<Vec<(CString, CString)>, Err
         .collect()
 }
 
-fn convert_values(
+fn for_each_value<F>(
     values: Vec<*mut c_char>,
     values_sizes: Vec<usize>,
     errors: Vec<*mut c_char>,
-) -> Vec<Result<Option<Vec<u8>>, Error>> {
-    values
+    mut f: F,
+) where
+    F: FnMut(usize, Result<Option<&[u8]>, Error>),
+{
+    for (i, ((v, s), e)) in values
         .into_iter()
         .zip(values_sizes.into_iter())
         .zip(errors.into_iter())
-        .map(|((v, s), e)| {
-            if e.is_null() {
-                let value = unsafe { crate::ffi_util::raw_data(v, s) };
-                unsafe {
-                    ffi::rocksdb_free(v as *mut c_void);
-                }
-                Ok(value)
-            } else {
-                Err(Error::new(crate::ffi_util::error_message(e)))
+        .enumerate()
+    {
+        if !e.is_null() {
+            f(i, Err(Error::new(crate::ffi_util::error_message(e))));
+        } else if v.is_null() {
+            f(i, Ok(None));
+        } else {
+            f(
+                i,
+                Ok(Some(unsafe { slice::from_raw_parts(v as *const u8, s) })),
+            );
+            unsafe {
+                ffi::rocksdb_free(v as *mut c_void);
             }
-        })
-        .collect()
+        }
+    }
+}
+
+fn copy_into(
+    values: &mut Vec<Vec<u8>>,
+    index: usize,
+    value: Result<Option<&[u8]>, Error>,
+) -> Result<bool, Error> {
+    if values.len() <= index {
+        values.push(Vec::new());
+    }
+    let buffer = &mut values[index];
+    buffer.clear();
+    match value? {
+        Some(value) => {
+            buffer.extend_from_slice(value);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
 }
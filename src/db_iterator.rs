@@ -12,11 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::db::{DBAccess, DB};
-use crate::{ffi, Error, ReadOptions, WriteBatch};
+use crate::db::{DBAccess, DBWithThreadMode, ThreadMode, DB};
+use crate::{ffi, AsColumnFamilyRef, Error, OwnedSnapshot, ReadOptions, WriteBatch};
 use libc::{c_char, c_uchar, size_t};
 use std::marker::PhantomData;
 use std::slice;
+use std::sync::Arc;
 
 /// A type alias to keep compatibility. See [`DBRawIteratorWithThreadMode`] for details
 pub type DBRawIterator<'a> = DBRawIteratorWithThreadMode<'a, DB>;
@@ -488,6 +489,225 @@ impl<'a, D: DBAccess> Into<DBRawIteratorWithThreadMode<'a, D>> for DBIteratorWit
     }
 }
 
+/// An iterator over a database or column family which owns a reference to the
+/// database instead of borrowing it.
+///
+/// Unlike [`DBIteratorWithThreadMode`], an `OwnedIterator` is `'static`, so it
+/// can be stored in long-lived structs or moved to another thread or task.
+/// Iterators created from an [`OwnedSnapshot`] keep the snapshot alive.
+///
+/// ```
+/// use rocksdb::{DB, IteratorMode, Options, OwnedIterator, ReadOptions};
+/// use std::sync::Arc;
+///
+/// let path = "_path_for_rocksdb_storage_owned_iterator";
+/// {
+///     let db = Arc::new(DB::open_default(path).unwrap());
+///     db.put(b"k1", b"v1").unwrap();
+///     let iter = OwnedIterator::new(db.clone(), ReadOptions::default(), IteratorMode::Start);
+///     let handle = std::thread::spawn(move || iter.count());
+///     assert_eq!(handle.join().unwrap(), 1);
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+pub struct OwnedIterator<T: ThreadMode + 'static> {
+    // Declared first so that the iterator is destroyed before the snapshot
+    // and the database it reads from.
+    iter: DBIteratorWithThreadMode<'static, DBWithThreadMode<T>>,
+    _snapshot: Option<OwnedSnapshot<T>>,
+    db: Arc<DBWithThreadMode<T>>,
+}
+
+impl<T: ThreadMode + 'static> OwnedIterator<T> {
+    /// Creates an iterator over the database `db`, using the given read options.
+    pub fn new(db: Arc<DBWithThreadMode<T>>, readopts: ReadOptions, mode: IteratorMode) -> Self {
+        Self::build(db, None, readopts, mode, None)
+    }
+
+    /// Creates an iterator over the given column family of the database `db`,
+    /// using the given read options.
+    pub fn new_cf(
+        db: Arc<DBWithThreadMode<T>>,
+        cf_handle: &impl AsColumnFamilyRef,
+        readopts: ReadOptions,
+        mode: IteratorMode,
+    ) -> Self {
+        Self::build(db, Some(cf_handle.inner()), readopts, mode, None)
+    }
+
+    pub(crate) fn build(
+        db: Arc<DBWithThreadMode<T>>,
+        cf_handle: Option<*mut ffi::rocksdb_column_family_handle_t>,
+        readopts: ReadOptions,
+        mode: IteratorMode,
+        snapshot: Option<OwnedSnapshot<T>>,
+    ) -> Self {
+        // SAFETY: the database is kept alive by `db` for as long as the
+        // iterator exists, see the field order of `OwnedIterator`.
+        let db_ref: &'static DBWithThreadMode<T> = unsafe { &*Arc::as_ptr(&db) };
+        let iter = match cf_handle {
+            Some(cf_handle) => DBIteratorWithThreadMode::new_cf(db_ref, cf_handle, readopts, mode),
+            None => DBIteratorWithThreadMode::new(db_ref, readopts, mode),
+        };
+        Self {
+            iter,
+            _snapshot: snapshot,
+            db,
+        }
+    }
+
+    /// Returns the database this iterator reads from.
+    pub fn db(&self) -> &Arc<DBWithThreadMode<T>> {
+        &self.db
+    }
+
+    /// See [`set_mode`](DBIteratorWithThreadMode::set_mode)
+    pub fn set_mode(&mut self, mode: IteratorMode) {
+        self.iter.set_mode(mode);
+    }
+
+    /// See [`valid`](DBRawIteratorWithThreadMode::valid)
+    pub fn valid(&self) -> bool {
+        self.iter.valid()
+    }
+
+    /// See [`status`](DBRawIteratorWithThreadMode::status)
+    pub fn status(&self) -> Result<(), Error> {
+        self.iter.status()
+    }
+}
+
+impl<T: ThreadMode + 'static> Iterator for OwnedIterator<T> {
+    type Item = KVBytes;
+
+    fn next(&mut self) -> Option<KVBytes> {
+        self.iter.next()
+    }
+}
+
+/// A raw iterator over a database or column family which owns a reference to
+/// the database instead of borrowing it.
+///
+/// This is the owned counterpart of [`DBRawIteratorWithThreadMode`], see
+/// [`OwnedIterator`].
+///
+/// ```
+/// use rocksdb::{DB, Options, OwnedRawIterator, ReadOptions};
+/// use std::sync::Arc;
+///
+/// let path = "_path_for_rocksdb_storage_owned_raw_iterator";
+/// {
+///     let db = Arc::new(DB::open_default(path).unwrap());
+///     db.put(b"k1", b"v1").unwrap();
+///     let mut iter = OwnedRawIterator::new(db.clone(), ReadOptions::default());
+///     let handle = std::thread::spawn(move || {
+///         iter.seek_to_first();
+///         iter.key().map(<[u8]>::to_vec)
+///     });
+///     assert_eq!(handle.join().unwrap().unwrap(), b"k1");
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+pub struct OwnedRawIterator<T: ThreadMode + 'static> {
+    // Declared first so that the iterator is destroyed before the snapshot
+    // and the database it reads from.
+    raw: DBRawIteratorWithThreadMode<'static, DBWithThreadMode<T>>,
+    _snapshot: Option<OwnedSnapshot<T>>,
+    db: Arc<DBWithThreadMode<T>>,
+}
+
+impl<T: ThreadMode + 'static> OwnedRawIterator<T> {
+    /// Creates a raw iterator over the database `db`, using the given read options.
+    pub fn new(db: Arc<DBWithThreadMode<T>>, readopts: ReadOptions) -> Self {
+        Self::build(db, None, readopts, None)
+    }
+
+    /// Creates a raw iterator over the given column family of the database
+    /// `db`, using the given read options.
+    pub fn new_cf(
+        db: Arc<DBWithThreadMode<T>>,
+        cf_handle: &impl AsColumnFamilyRef,
+        readopts: ReadOptions,
+    ) -> Self {
+        Self::build(db, Some(cf_handle.inner()), readopts, None)
+    }
+
+    pub(crate) fn build(
+        db: Arc<DBWithThreadMode<T>>,
+        cf_handle: Option<*mut ffi::rocksdb_column_family_handle_t>,
+        readopts: ReadOptions,
+        snapshot: Option<OwnedSnapshot<T>>,
+    ) -> Self {
+        // SAFETY: the database is kept alive by `db` for as long as the
+        // iterator exists, see the field order of `OwnedRawIterator`.
+        let db_ref: &'static DBWithThreadMode<T> = unsafe { &*Arc::as_ptr(&db) };
+        let raw = match cf_handle {
+            Some(cf_handle) => DBRawIteratorWithThreadMode::new_cf(db_ref, cf_handle, readopts),
+            None => DBRawIteratorWithThreadMode::new(db_ref, readopts),
+        };
+        Self {
+            raw,
+            _snapshot: snapshot,
+            db,
+        }
+    }
+
+    /// Returns the database this iterator reads from.
+    pub fn db(&self) -> &Arc<DBWithThreadMode<T>> {
+        &self.db
+    }
+
+    /// See [`valid`](DBRawIteratorWithThreadMode::valid)
+    pub fn valid(&self) -> bool {
+        self.raw.valid()
+    }
+
+    /// See [`status`](DBRawIteratorWithThreadMode::status)
+    pub fn status(&self) -> Result<(), Error> {
+        self.raw.status()
+    }
+
+    /// See [`seek_to_first`](DBRawIteratorWithThreadMode::seek_to_first)
+    pub fn seek_to_first(&mut self) {
+        self.raw.seek_to_first();
+    }
+
+    /// See [`seek_to_last`](DBRawIteratorWithThreadMode::seek_to_last)
+    pub fn seek_to_last(&mut self) {
+        self.raw.seek_to_last();
+    }
+
+    /// See [`seek`](DBRawIteratorWithThreadMode::seek)
+    pub fn seek<K: AsRef<[u8]>>(&mut self, key: K) {
+        self.raw.seek(key);
+    }
+
+    /// See [`seek_for_prev`](DBRawIteratorWithThreadMode::seek_for_prev)
+    pub fn seek_for_prev<K: AsRef<[u8]>>(&mut self, key: K) {
+        self.raw.seek_for_prev(key);
+    }
+
+    /// See [`next`](DBRawIteratorWithThreadMode::next)
+    pub fn next(&mut self) {
+        self.raw.next();
+    }
+
+    /// See [`prev`](DBRawIteratorWithThreadMode::prev)
+    pub fn prev(&mut self) {
+        self.raw.prev();
+    }
+
+    /// See [`key`](DBRawIteratorWithThreadMode::key)
+    pub fn key(&self) -> Option<&[u8]> {
+        self.raw.key()
+    }
+
+    /// See [`value`](DBRawIteratorWithThreadMode::value)
+    pub fn value(&self) -> Option<&[u8]> {
+        self.raw.value()
+    }
+}
+
 /// Iterates the batches of writes since a given sequence number.
 ///
 /// `DBWALIterator` is returned by `DB::get_updates_since()` and will return the
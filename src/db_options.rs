@@ -14,7 +14,8 @@
 
 use std::ffi::{CStr, CString};
 use std::mem;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::ptr;
 use std::sync::Arc;
 
 use libc::{self, c_char, c_int, c_uchar, c_uint, c_void, size_t};
@@ -25,6 +26,7 @@ use crate::{
     comparator::{self, ComparatorCallback, CompareFn},
     db::DBAccess,
     ffi,
+    ffi_util::error_message,
     merge_operator::{
         self, full_merge_callback, partial_merge_callback, MergeFn, MergeOperatorCallback,
     },
@@ -72,6 +74,11 @@ impl Cache {
         unsafe { ffi::rocksdb_cache_get_pinned_usage(self.0.inner) }
     }
 
+    /// Returns the capacity of the cache
+    pub fn get_capacity(&self) -> usize {
+        unsafe { ffi::rocksdb_cache_get_capacity(self.0.inner) }
+    }
+
     /// Sets cache capacity
     pub fn set_capacity(&mut self, capacity: size_t) {
         unsafe {
@@ -80,6 +87,64 @@ impl Cache {
     }
 }
 
+pub(crate) struct RateLimiterWrapper {
+    pub(crate) inner: *mut ffi::rocksdb_ratelimiter_t,
+}
+
+impl Drop for RateLimiterWrapper {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_ratelimiter_destroy(self.inner);
+        }
+    }
+}
+
+/// Controls the rate of flush and compaction writes.
+///
+/// Setting the same `RateLimiter` on the options of several databases makes
+/// them share one I/O budget. The limiter is reference counted by RocksDB, so
+/// it may be dropped while the databases using it are still open.
+///
+/// The C API only exposes the generic rate limiter with its default mode
+/// (limiting writes), without auto-tuning, and its rate can't be changed or
+/// queried once it has been created.
+///
+/// # Examples
+///
+/// ```
+/// use rocksdb::{Options, RateLimiter};
+///
+/// let limiter = RateLimiter::new(16 << 20, 100 * 1000, 10).unwrap();
+/// let mut opts_a = Options::default();
+/// opts_a.set_rate_limiter(&limiter);
+/// let mut opts_b = Options::default();
+/// opts_b.set_rate_limiter(&limiter);
+/// ```
+#[derive(Clone)]
+pub struct RateLimiter(pub(crate) Arc<RateLimiterWrapper>);
+
+impl RateLimiter {
+    /// Creates a rate limiter allowing `rate_bytes_per_sec` bytes per second.
+    ///
+    /// Tokens are refilled every `refill_period_us` microseconds, and low
+    /// priority requests get a chance to run ahead of high priority ones once
+    /// every `fairness` requests.
+    pub fn new(
+        rate_bytes_per_sec: i64,
+        refill_period_us: i64,
+        fairness: i32,
+    ) -> Result<RateLimiter, Error> {
+        let limiter = unsafe {
+            ffi::rocksdb_ratelimiter_create(rate_bytes_per_sec, refill_period_us, fairness)
+        };
+        if limiter.is_null() {
+            Err(Error::new("Could not create RateLimiter".to_owned()))
+        } else {
+            Ok(RateLimiter(Arc::new(RateLimiterWrapper { inner: limiter })))
+        }
+    }
+}
+
 /// An Env is an interface used by the rocksdb implementation to access
 /// operating system functionality like the filesystem etc.  Callers
 /// may wish to provide a custom Env object when opening a database to
@@ -205,6 +270,7 @@ impl Env {
 pub(crate) struct OptionsMustOutliveDB {
     env: Option<Env>,
     row_cache: Option<Cache>,
+    rate_limiter: Option<RateLimiter>,
     block_based: Option<BlockBasedOptionsMustOutliveDB>,
 }
 
@@ -213,6 +279,7 @@ impl OptionsMustOutliveDB {
         Self {
             env: self.env.as_ref().map(Env::clone),
             row_cache: self.row_cache.as_ref().map(Cache::clone),
+            rate_limiter: self.rate_limiter.as_ref().map(RateLimiter::clone),
             block_based: self
                 .block_based
                 .as_ref()
@@ -236,6 +303,29 @@ impl BlockBasedOptionsMustOutliveDB {
     }
 }
 
+/// Names of the callbacks and the table factory set on [`Options`], as well as
+/// the table options and the options the C API has no getter for, kept so
+/// that they can be read back, written to and verified against OPTIONS files.
+#[derive(Clone, Default)]
+pub(crate) struct OptionsNames {
+    pub(crate) comparator: Option<String>,
+    pub(crate) merge_operator: Option<String>,
+    pub(crate) compaction_filter: Option<String>,
+    pub(crate) compaction_filter_factory: Option<String>,
+    pub(crate) prefix_extractor: Option<String>,
+    pub(crate) table_factory: Option<&'static str>,
+    pub(crate) table_options: Vec<(String, String)>,
+    pub(crate) ttl: Option<u64>,
+    pub(crate) periodic_compaction_seconds: Option<u64>,
+    pub(crate) sample_for_compression: Option<u64>,
+    pub(crate) wal_dir: Option<PathBuf>,
+    pub(crate) db_log_dir: Option<PathBuf>,
+    pub(crate) manual_wal_flush: bool,
+    pub(crate) dump_malloc_stats: bool,
+    pub(crate) compression_per_level: Option<Vec<DBCompressionType>>,
+    pub(crate) max_bytes_for_level_multiplier_additional: Option<Vec<i32>>,
+}
+
 /// Database-wide options around performance and behavior.
 ///
 /// Please read the official tuning [guide](https://github.com/facebook/rocksdb/wiki/RocksDB-Tuning-Guide)
@@ -273,6 +363,7 @@ impl BlockBasedOptionsMustOutliveDB {
 pub struct Options {
     pub(crate) inner: *mut ffi::rocksdb_options_t,
     pub(crate) outlive: OptionsMustOutliveDB,
+    pub(crate) names: OptionsNames,
 }
 
 /// Optionally disable WAL or sync for this write.
@@ -332,6 +423,9 @@ pub struct FlushOptions {
 pub struct BlockBasedOptions {
     pub(crate) inner: *mut ffi::rocksdb_block_based_table_options_t,
     outlive: BlockBasedOptionsMustOutliveDB,
+    // Values set so far, named as in OPTIONS files, since the C API has no
+    // getters for them.
+    pub(crate) values: Vec<(String, String)>,
 }
 
 pub struct ReadOptions {
@@ -384,6 +478,7 @@ unsafe impl Send for ReadOptions {}
 unsafe impl Send for IngestExternalFileOptions {}
 unsafe impl Send for Cache {}
 unsafe impl Send for Env {}
+unsafe impl Send for RateLimiterWrapper {}
 
 // Sync is similarly safe for many types because they do not expose interior mutability, and their
 // use within the rocksdb library is generally behind a const reference
@@ -395,6 +490,7 @@ unsafe impl Sync for ReadOptions {}
 unsafe impl Sync for IngestExternalFileOptions {}
 unsafe impl Sync for Cache {}
 unsafe impl Sync for Env {}
+unsafe impl Sync for RateLimiterWrapper {}
 
 impl Drop for Options {
     fn drop(&mut self) {
@@ -413,6 +509,7 @@ impl Clone for Options {
         Self {
             inner,
             outlive: self.outlive.clone(),
+            names: self.names.clone(),
         }
     }
 }
@@ -474,6 +571,7 @@ impl BlockBasedOptions {
         unsafe {
             ffi::rocksdb_block_based_options_set_block_size(self.inner, size);
         }
+        self.record("block_size", size);
     }
 
     /// Block size for partitioned metadata. Currently applied to indexes when
@@ -488,6 +586,7 @@ impl BlockBasedOptions {
         unsafe {
             ffi::rocksdb_block_based_options_set_metadata_block_size(self.inner, size as u64);
         }
+        self.record("metadata_block_size", size);
     }
 
     /// Note: currently this option requires kTwoLevelIndexSearch to be set as
@@ -499,6 +598,7 @@ impl BlockBasedOptions {
         unsafe {
             ffi::rocksdb_block_based_options_set_partition_filters(self.inner, size as c_uchar);
         }
+        self.record("partition_filters", size);
     }
 
     /// When provided: use the specified cache for blocks.
@@ -561,25 +661,76 @@ impl BlockBasedOptions {
         unsafe {
             ffi::rocksdb_block_based_options_set_no_block_cache(self.inner, true as c_uchar);
         }
+        self.record("no_block_cache", true);
     }
 
     /// Sets the filter policy to reduce disk reads
     pub fn set_bloom_filter(&mut self, bits_per_key: c_int, block_based: bool) {
+        self.set_filter_policy(if block_based {
+            FilterPolicy::BlockBasedBloom { bits_per_key }
+        } else {
+            FilterPolicy::Bloom { bits_per_key }
+        });
+    }
+
+    /// Sets the filter policy used to skip reading data blocks which can't
+    /// contain a key.
+    ///
+    /// Combined with [`set_partition_filters`](Self::set_partition_filters) and
+    /// [`BlockBasedIndexType::TwoLevelIndexSearch`], full filters are split into
+    /// partitions that are loaded on demand, which bounds the memory used by
+    /// filters that aren't cached.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rocksdb::{BlockBasedIndexType, BlockBasedOptions, FilterPolicy};
+    ///
+    /// let mut block_opts = BlockBasedOptions::default();
+    /// block_opts.set_filter_policy(FilterPolicy::Bloom { bits_per_key: 10 });
+    /// block_opts.set_partition_filters(true);
+    /// block_opts.set_index_type(BlockBasedIndexType::TwoLevelIndexSearch);
+    /// ```
+    pub fn set_filter_policy(&mut self, policy: FilterPolicy) {
+        let (filter, bits_per_key, block_based) = unsafe {
+            match policy {
+                FilterPolicy::Bloom { bits_per_key } => (
+                    ffi::rocksdb_filterpolicy_create_bloom_full(bits_per_key),
+                    bits_per_key,
+                    false,
+                ),
+                FilterPolicy::BlockBasedBloom { bits_per_key } => (
+                    ffi::rocksdb_filterpolicy_create_bloom(bits_per_key),
+                    bits_per_key,
+                    true,
+                ),
+            }
+        };
         unsafe {
-            let bloom = if block_based {
-                ffi::rocksdb_filterpolicy_create_bloom(bits_per_key)
-            } else {
-                ffi::rocksdb_filterpolicy_create_bloom_full(bits_per_key)
-            };
+            ffi::rocksdb_block_based_options_set_filter_policy(self.inner, filter);
+        }
+        self.record(
+            "filter_policy",
+            format!("bloomfilter:{}:{}", bits_per_key, block_based),
+        );
+    }
 
-            ffi::rocksdb_block_based_options_set_filter_policy(self.inner, bloom);
+    /// If true, place whole keys in the filter (not just prefixes).
+    /// This must generally be true for gets to be efficient.
+    ///
+    /// Default: true
+    pub fn set_whole_key_filtering(&mut self, v: bool) {
+        unsafe {
+            ffi::rocksdb_block_based_options_set_whole_key_filtering(self.inner, v as c_uchar);
         }
+        self.record("whole_key_filtering", v);
     }
 
     pub fn set_cache_index_and_filter_blocks(&mut self, v: bool) {
         unsafe {
             ffi::rocksdb_block_based_options_set_cache_index_and_filter_blocks(self.inner, v as u8);
         }
+        self.record("cache_index_and_filter_blocks", v);
     }
 
     /// Defines the index type to be used for SS-table lookups.
@@ -594,10 +745,16 @@ impl BlockBasedOptions {
     /// block_opts.set_index_type(BlockBasedIndexType::HashSearch);
     /// ```
     pub fn set_index_type(&mut self, index_type: BlockBasedIndexType) {
+        let name = match index_type {
+            BlockBasedIndexType::BinarySearch => "kBinarySearch",
+            BlockBasedIndexType::HashSearch => "kHashSearch",
+            BlockBasedIndexType::TwoLevelIndexSearch => "kTwoLevelIndexSearch",
+        };
         let index = index_type as i32;
         unsafe {
             ffi::rocksdb_block_based_options_set_index_type(self.inner, index);
         }
+        self.record("index_type", name);
     }
 
     /// If cache_index_and_filter_blocks is true and the below is true, then
@@ -613,6 +770,7 @@ impl BlockBasedOptions {
                 v as c_uchar,
             );
         }
+        self.record("pin_l0_filter_and_index_blocks_in_cache", v);
     }
 
     /// If cache_index_and_filter_blocks is true and the below is true, then
@@ -629,6 +787,7 @@ impl BlockBasedOptions {
                 v as c_uchar,
             );
         }
+        self.record("pin_top_level_index_and_filter", v);
     }
 
     /// Format version, reserved for backward compatibility.
@@ -641,6 +800,7 @@ impl BlockBasedOptions {
         unsafe {
             ffi::rocksdb_block_based_options_set_format_version(self.inner, version);
         }
+        self.record("format_version", version);
     }
 
     /// Number of keys between restart points for delta encoding of keys.
@@ -653,6 +813,7 @@ impl BlockBasedOptions {
         unsafe {
             ffi::rocksdb_block_based_options_set_block_restart_interval(self.inner, interval);
         }
+        self.record("block_restart_interval", interval);
     }
 
     /// Same as block_restart_interval but used for the index block.
@@ -665,6 +826,7 @@ impl BlockBasedOptions {
         unsafe {
             ffi::rocksdb_block_based_options_set_index_block_restart_interval(self.inner, interval);
         }
+        self.record("index_block_restart_interval", interval);
     }
 
     /// Set the data block index type for point lookups:
@@ -687,10 +849,15 @@ impl BlockBasedOptions {
     /// block_opts.set_data_block_hash_ratio(0.85);
     /// ```
     pub fn set_data_block_index_type(&mut self, index_type: DataBlockIndexType) {
+        let name = match index_type {
+            DataBlockIndexType::BinarySearch => "kDataBlockBinarySearch",
+            DataBlockIndexType::BinaryAndHash => "kDataBlockBinaryAndHash",
+        };
         let index_t = index_type as i32;
         unsafe {
             ffi::rocksdb_block_based_options_set_data_block_index_type(self.inner, index_t);
         }
+        self.record("data_block_index_type", name);
     }
 
     /// Set the data block hash index utilization ratio.
@@ -704,6 +871,16 @@ impl BlockBasedOptions {
         unsafe {
             ffi::rocksdb_block_based_options_set_data_block_hash_ratio(self.inner, ratio);
         }
+        self.record("data_block_hash_table_util_ratio", ratio);
+    }
+
+    /// Records the value of the option `name` for OPTIONS files.
+    pub(crate) fn record(&mut self, name: &str, value: impl ToString) {
+        let value = value.to_string();
+        match self.values.iter_mut().find(|(n, _)| n == name) {
+            Some(entry) => entry.1 = value,
+            None => self.values.push((name.to_owned(), value)),
+        }
     }
 }
 
@@ -716,6 +893,7 @@ impl Default for BlockBasedOptions {
         Self {
             inner: block_opts,
             outlive: BlockBasedOptionsMustOutliveDB::default(),
+            values: Vec::new(),
         }
     }
 }
@@ -1026,6 +1204,14 @@ impl Options {
                 level_types.len() as size_t,
             );
         }
+        self.names.compression_per_level = Some(level_types.to_vec());
+    }
+
+    /// Returns the value set by
+    /// [`set_compression_per_level`](Self::set_compression_per_level), or
+    /// `None` if it isn't set.
+    pub fn get_compression_per_level(&self) -> Option<&[DBCompressionType]> {
+        self.names.compression_per_level.as_deref()
     }
 
     /// Maximum size of dictionaries used to prime the compression library.
@@ -1084,6 +1270,125 @@ impl Options {
         }
     }
 
+    /// Limits the amount of data buffered in memory to sample the compression
+    /// dictionary, see [`set_compression_options`](Self::set_compression_options).
+    /// Once the limit is reached, the dictionary is finalized from the data
+    /// sampled so far.
+    ///
+    /// Default: `0` (unlimited)
+    pub fn set_max_dict_buffer_bytes(&mut self, value: u64) {
+        unsafe {
+            ffi::rocksdb_options_set_compression_options_max_dict_buffer_bytes(self.inner, value);
+        }
+    }
+
+    /// Sets the compression algorithm used for the bottommost level, which
+    /// usually holds most of the data. If not set, the bottommost level uses
+    /// the compression of [`set_compression_type`](Self::set_compression_type)
+    /// or [`set_compression_per_level`](Self::set_compression_per_level).
+    ///
+    /// Default: not set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rocksdb::{Options, DBCompressionType};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_compression_type(DBCompressionType::Lz4);
+    /// opts.set_bottommost_compression_type(DBCompressionType::Zstd);
+    /// opts.set_bottommost_compression_options(-14, 19, 0, 16 * 1024, true);
+    /// opts.set_bottommost_zstd_max_train_bytes(100 * 16 * 1024, true);
+    /// ```
+    pub fn set_bottommost_compression_type(&mut self, t: DBCompressionType) {
+        unsafe {
+            ffi::rocksdb_options_set_bottommost_compression(self.inner, t as c_int);
+        }
+    }
+
+    /// Different from [`set_compression_options`](Self::set_compression_options),
+    /// these options apply to the bottommost level only, and only if `enabled`
+    /// is true.
+    pub fn set_bottommost_compression_options(
+        &mut self,
+        w_bits: c_int,
+        level: c_int,
+        strategy: c_int,
+        max_dict_bytes: c_int,
+        enabled: bool,
+    ) {
+        unsafe {
+            ffi::rocksdb_options_set_bottommost_compression_options(
+                self.inner,
+                w_bits,
+                level,
+                strategy,
+                max_dict_bytes,
+                enabled as c_uchar,
+            );
+        }
+    }
+
+    /// Sets maximum size of training data passed to zstd's dictionary trainer
+    /// for the bottommost level, see
+    /// [`set_zstd_max_train_bytes`](Self::set_zstd_max_train_bytes).
+    ///
+    /// `enabled` enables the bottommost compression options even if
+    /// [`set_bottommost_compression_options`](Self::set_bottommost_compression_options)
+    /// wasn't called.
+    pub fn set_bottommost_zstd_max_train_bytes(&mut self, value: c_int, enabled: bool) {
+        unsafe {
+            ffi::rocksdb_options_set_bottommost_compression_options_zstd_max_train_bytes(
+                self.inner,
+                value,
+                enabled as c_uchar,
+            );
+        }
+    }
+
+    /// Limits the data buffered to sample the bottommost level compression
+    /// dictionary, see [`set_max_dict_buffer_bytes`](Self::set_max_dict_buffer_bytes).
+    pub fn set_bottommost_max_dict_buffer_bytes(&mut self, value: u64, enabled: bool) {
+        unsafe {
+            ffi::rocksdb_options_set_bottommost_compression_options_max_dict_buffer_bytes(
+                self.inner,
+                value,
+                enabled as c_uchar,
+            );
+        }
+    }
+
+    /// If non-zero, compresses one data block out of every `ratio` with
+    /// Snappy and ZSTD while writing SST files, and reports the compressed
+    /// sizes in the `rocksdb.sampled.*` statistics. This helps estimating the
+    /// benefit of enabling compression.
+    ///
+    /// Default: 0
+    ///
+    /// Dynamically changeable through SetOptions() API
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_sample_for_compression(10).unwrap();
+    /// assert_eq!(opts.get_sample_for_compression(), Some(10));
+    /// ```
+    pub fn set_sample_for_compression(&mut self, ratio: u64) -> Result<(), Error> {
+        self.set_from_string(&format!("sample_for_compression={}", ratio))?;
+        self.names.sample_for_compression = Some(ratio);
+        Ok(())
+    }
+
+    /// Returns the value set by
+    /// [`set_sample_for_compression`](Self::set_sample_for_compression), or
+    /// `None` if it isn't set.
+    pub fn get_sample_for_compression(&self) -> Option<u64> {
+        self.names.sample_for_compression
+    }
+
     /// If non-zero, we perform bigger reads when doing compaction. If you're
     /// running RocksDB on spinning disks, you should set this to at least 2MB.
     /// That way RocksDB's compaction is doing sequential instead of random reads.
@@ -1134,6 +1439,7 @@ impl Options {
             );
             ffi::rocksdb_options_set_merge_operator(self.inner, mo);
         }
+        self.names.merge_operator = Some(name.to_owned());
     }
 
     pub fn set_merge_operator<F: MergeFn, PF: MergeFn>(
@@ -1159,6 +1465,7 @@ impl Options {
             );
             ffi::rocksdb_options_set_merge_operator(self.inner, mo);
         }
+        self.names.merge_operator = Some(name.to_owned());
     }
 
     #[deprecated(
@@ -1197,6 +1504,7 @@ impl Options {
             );
             ffi::rocksdb_options_set_compaction_filter(self.inner, cf);
         }
+        self.names.compaction_filter = Some(name.to_owned());
     }
 
     /// This is a factory that provides compaction filter objects which allow
@@ -1211,6 +1519,7 @@ impl Options {
     where
         F: CompactionFilterFactory + 'static,
     {
+        self.names.compaction_filter_factory = Some(factory.name().to_string_lossy().into_owned());
         let factory = Box::new(factory);
 
         unsafe {
@@ -1246,12 +1555,14 @@ impl Options {
             );
             ffi::rocksdb_options_set_comparator(self.inner, cmp);
         }
+        self.names.comparator = Some(name.to_owned());
     }
 
     pub fn set_prefix_extractor(&mut self, prefix_extractor: SliceTransform) {
         unsafe {
             ffi::rocksdb_options_set_prefix_extractor(self.inner, prefix_extractor.inner);
         }
+        self.names.prefix_extractor = Some(prefix_extractor.name);
     }
 
     #[deprecated(
@@ -1378,6 +1689,22 @@ impl Options {
         unsafe {
             ffi::rocksdb_options_set_db_log_dir(self.inner, p.as_ptr());
         }
+        self.names.db_log_dir = Some(path.as_ref().to_owned());
+    }
+
+    /// Returns the value set by [`set_db_log_dir`](Self::set_db_log_dir), or
+    /// `None` if it isn't set.
+    pub fn get_db_log_dir(&self) -> Option<&Path> {
+        self.names.db_log_dir.as_deref()
+    }
+
+    /// Sets the minimum level of the messages written to the info LOG.
+    ///
+    /// Default: `LogLevel::Info`
+    pub fn set_info_log_level(&mut self, level: LogLevel) {
+        unsafe {
+            ffi::rocksdb_options_set_info_log_level(self.inner, level as c_int);
+        }
     }
 
     /// Allows OS to incrementally sync files to disk while they are being
@@ -2160,6 +2487,16 @@ impl Options {
                 count,
             );
         }
+        self.names.max_bytes_for_level_multiplier_additional = Some(level_values.to_vec());
+    }
+
+    /// Returns the value set by
+    /// [`set_max_bytes_for_level_multiplier_additional`](Self::set_max_bytes_for_level_multiplier_additional),
+    /// or `None` if it isn't set.
+    pub fn get_max_bytes_for_level_multiplier_additional(&self) -> Option<&[i32]> {
+        self.names
+            .max_bytes_for_level_multiplier_additional
+            .as_deref()
     }
 
     /// If true, then DB::Open() will not fetch and check sizes of all sst files.
@@ -2280,6 +2617,8 @@ impl Options {
             ffi::rocksdb_options_set_block_based_table_factory(self.inner, factory.inner);
         }
         self.outlive.block_based = Some(factory.outlive.clone());
+        self.names.table_factory = Some("BlockBasedTable");
+        self.names.table_options.clone_from(&factory.values);
     }
 
     /// Sets the table factory to a CuckooTableFactory (the default table
@@ -2306,6 +2645,8 @@ impl Options {
         unsafe {
             ffi::rocksdb_options_set_cuckoo_table_factory(self.inner, factory.inner);
         }
+        self.names.table_factory = Some("CuckooTable");
+        self.names.table_options = Vec::new();
     }
 
     // This is a factory that provides TableFactory objects.
@@ -2341,6 +2682,25 @@ impl Options {
                 options.index_sparseness,
             );
         }
+        self.names.table_factory = Some("PlainTable");
+        self.names.table_options = vec![
+            (
+                "user_key_len".to_owned(),
+                options.user_key_length.to_string(),
+            ),
+            (
+                "bloom_bits_per_key".to_owned(),
+                options.bloom_bits_per_key.to_string(),
+            ),
+            (
+                "hash_table_ratio".to_owned(),
+                options.hash_table_ratio.to_string(),
+            ),
+            (
+                "index_sparseness".to_owned(),
+                options.index_sparseness.to_string(),
+            ),
+        ];
     }
 
     /// Sets the start level to use compression.
@@ -2540,6 +2900,93 @@ impl Options {
         }
     }
 
+    /// Files containing updates older than TTL will go through the compaction
+    /// process, so that those entries are compacted to the bottommost level.
+    /// With FIFO compaction, files older than TTL are deleted.
+    ///
+    /// Requires `max_open_files` to be -1. `0` disables the feature.
+    ///
+    /// Default: 30 days for leveled compaction with block based tables,
+    /// disabled otherwise
+    ///
+    /// Dynamically changeable through SetOptions() API
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_ttl(24 * 60 * 60).unwrap();
+    /// assert_eq!(opts.get_ttl(), Some(24 * 60 * 60));
+    /// ```
+    pub fn set_ttl(&mut self, secs: u64) -> Result<(), Error> {
+        self.set_from_string(&format!("ttl={}", secs))?;
+        self.names.ttl = Some(secs);
+        Ok(())
+    }
+
+    /// Returns the value set by [`set_ttl`](Self::set_ttl), or `None` if
+    /// RocksDB picks the default when the database is opened.
+    pub fn get_ttl(&self) -> Option<u64> {
+        self.names.ttl
+    }
+
+    /// Files older than this value will be picked up for compaction, and
+    /// re-written to the same level as they were before. This makes sure that
+    /// every file goes through the compaction filter periodically.
+    ///
+    /// Supported in level and FIFO compaction. Requires `max_open_files` to be
+    /// -1. `0` turns periodic compactions off.
+    ///
+    /// Default: 30 days if a compaction filter is set, disabled otherwise
+    ///
+    /// Dynamically changeable through SetOptions() API
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_periodic_compaction_seconds(7 * 24 * 60 * 60).unwrap();
+    /// assert_eq!(opts.get_periodic_compaction_seconds(), Some(7 * 24 * 60 * 60));
+    /// ```
+    pub fn set_periodic_compaction_seconds(&mut self, secs: u64) -> Result<(), Error> {
+        self.set_from_string(&format!("periodic_compaction_seconds={}", secs))?;
+        self.names.periodic_compaction_seconds = Some(secs);
+        Ok(())
+    }
+
+    /// Returns the value set by
+    /// [`set_periodic_compaction_seconds`](Self::set_periodic_compaction_seconds),
+    /// or `None` if RocksDB picks the default when the database is opened.
+    pub fn get_periodic_compaction_seconds(&self) -> Option<u64> {
+        self.names.periodic_compaction_seconds
+    }
+
+    /// Applies `name=value;` pairs on top of these options. Used for options
+    /// that have no setter in the C API.
+    ///
+    /// The C API can only parse options into a new copy, which replaces
+    /// `inner`; on error these options are left unchanged.
+    pub(crate) fn set_from_string(&mut self, options: &str) -> Result<(), Error> {
+        let options = CString::new(options)
+            .map_err(|_| Error::new("Failed to convert options string to CString".to_owned()))?;
+        unsafe {
+            let inner = ffi::rocksdb_options_create();
+            let mut err: *mut c_char = ptr::null_mut();
+            ffi::rocksdb_get_options_from_string(self.inner, options.as_ptr(), inner, &mut err);
+            if !err.is_null() {
+                ffi::rocksdb_options_destroy(inner);
+                return Err(Error::new(error_message(err)));
+            }
+            ffi::rocksdb_options_destroy(self.inner);
+            self.inner = inner;
+        }
+        Ok(())
+    }
+
     /// Specifies the absolute path of the directory the
     /// write-ahead log (WAL) should be written to.
     ///
@@ -2558,6 +3005,13 @@ impl Options {
         unsafe {
             ffi::rocksdb_options_set_wal_dir(self.inner, p.as_ptr());
         }
+        self.names.wal_dir = Some(path.as_ref().to_owned());
+    }
+
+    /// Returns the value set by [`set_wal_dir`](Self::set_wal_dir), or `None`
+    /// if it isn't set.
+    pub fn get_wal_dir(&self) -> Option<&Path> {
+        self.names.wal_dir.as_deref()
     }
 
     /// Sets the WAL ttl in seconds.
@@ -2700,6 +3154,12 @@ impl Options {
         unsafe {
             ffi::rocksdb_options_set_manual_wal_flush(self.inner, is_enabled as c_uchar);
         }
+        self.names.manual_wal_flush = is_enabled;
+    }
+
+    /// Returns the value set by [`set_manual_wal_flush`](Self::set_manual_wal_flush).
+    pub fn get_manual_wal_flush(&self) -> bool {
+        self.names.manual_wal_flush
     }
 
     /// Guarantee that all column families are flushed together atomically.
@@ -2740,6 +3200,10 @@ impl Options {
     /// priority than compaction.
     /// If rate limiter is enabled, bytes_per_sync is set to 1MB by default.
     ///
+    /// Does nothing if RocksDB fails to create the rate limiter; use
+    /// [`RateLimiter::new`] with [`set_rate_limiter`](Self::set_rate_limiter)
+    /// to handle that error.
+    ///
     /// Default: disable
     ///
     /// # Examples
@@ -2756,13 +3220,20 @@ impl Options {
         refill_period_us: i64,
         fairness: i32,
     ) {
+        if let Ok(limiter) = RateLimiter::new(rate_bytes_per_sec, refill_period_us, fairness) {
+            self.set_rate_limiter(&limiter);
+        }
+    }
+
+    /// Sets a rate limiter which may be shared with other databases, see
+    /// [`RateLimiter`].
+    ///
+    /// Default: disable
+    pub fn set_rate_limiter(&mut self, limiter: &RateLimiter) {
         unsafe {
-            let ratelimiter =
-                ffi::rocksdb_ratelimiter_create(rate_bytes_per_sec, refill_period_us, fairness);
-            // Since limiter is wrapped in shared_ptr, we don't need to
-            // call rocksdb_ratelimiter_destroy explicitly.
-            ffi::rocksdb_options_set_ratelimiter(self.inner, ratelimiter);
+            ffi::rocksdb_options_set_ratelimiter(self.inner, limiter.0.inner);
         }
+        self.outlive.rate_limiter = Some(limiter.clone());
     }
 
     /// Sets the maximal size of the info log file.
@@ -2897,6 +3368,12 @@ impl Options {
         unsafe {
             ffi::rocksdb_options_set_dump_malloc_stats(self.inner, enabled as c_uchar);
         }
+        self.names.dump_malloc_stats = enabled;
+    }
+
+    /// Returns the value set by [`set_dump_malloc_stats`](Self::set_dump_malloc_stats).
+    pub fn get_dump_malloc_stats(&self) -> bool {
+        self.names.dump_malloc_stats
     }
 
     /// Enable whole key bloom filter in memtable. Note this will only take effect
@@ -2914,6 +3391,393 @@ impl Options {
             );
         }
     }
+
+    /// Enables the integrated BlobDB: values at least
+    /// [`min_blob_size`](Self::set_min_blob_size) bytes long are written to
+    /// separate blob files during flush and compaction instead of being
+    /// stored in the SST files, which avoids rewriting them during compaction.
+    ///
+    /// Default: false
+    ///
+    /// Dynamically changeable through SetOptions() API
+    pub fn set_enable_blob_files(&mut self, val: bool) {
+        unsafe {
+            ffi::rocksdb_options_set_enable_blob_files(self.inner, val as c_uchar);
+        }
+    }
+
+    /// Sets the size of the smallest value to be stored separately in a blob
+    /// file. Values which have an uncompressed size smaller than this
+    /// threshold are stored alongside the keys in SST files in the usual
+    /// fashion.
+    ///
+    /// Default: 0
+    ///
+    /// Dynamically changeable through SetOptions() API
+    pub fn set_min_blob_size(&mut self, val: u64) {
+        unsafe {
+            ffi::rocksdb_options_set_min_blob_size(self.inner, val);
+        }
+    }
+
+    /// Sets the size limit for blob files. When writing blob files, a new
+    /// file is opened once this limit is reached.
+    ///
+    /// Default: 268435456 (256 MB)
+    ///
+    /// Dynamically changeable through SetOptions() API
+    pub fn set_blob_file_size(&mut self, size: u64) {
+        unsafe {
+            ffi::rocksdb_options_set_blob_file_size(self.inner, size);
+        }
+    }
+
+    /// Sets the compression algorithm to use for large values stored in blob
+    /// files.
+    ///
+    /// Default: `DBCompressionType::None`
+    ///
+    /// Dynamically changeable through SetOptions() API
+    pub fn set_blob_compression_type(&mut self, val: DBCompressionType) {
+        unsafe {
+            ffi::rocksdb_options_set_blob_compression_type(self.inner, val as c_int);
+        }
+    }
+
+    /// If this is set to true, RocksDB will actively relocate valid blobs
+    /// from the oldest blob files as they are encountered during compaction.
+    ///
+    /// Default: false
+    ///
+    /// Dynamically changeable through SetOptions() API
+    pub fn set_enable_blob_gc(&mut self, val: bool) {
+        unsafe {
+            ffi::rocksdb_options_set_enable_blob_gc(self.inner, val as c_uchar);
+        }
+    }
+
+    /// Sets the threshold that the garbage collection logic uses to determine
+    /// which blob files should be considered "old." For example, the default
+    /// value of 0.25 signals to RocksDB that blobs residing in the oldest 25%
+    /// of blob files should be relocated by GC. This parameter can be tuned
+    /// to adjust the trade-off between write amplification and space
+    /// amplification.
+    ///
+    /// Default: 0.25
+    ///
+    /// Dynamically changeable through SetOptions() API
+    pub fn set_blob_gc_age_cutoff(&mut self, val: f64) {
+        unsafe {
+            ffi::rocksdb_options_set_blob_gc_age_cutoff(self.inner, val);
+        }
+    }
+}
+
+impl Options {
+    /// Returns whether the database is created if it is missing, see [`create_if_missing`](Self::create_if_missing).
+    pub fn get_create_if_missing(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_create_if_missing(self.inner) != 0 }
+    }
+
+    /// Returns whether missing column families are created, see [`create_missing_column_families`](Self::create_missing_column_families).
+    pub fn get_create_missing_column_families(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_create_missing_column_families(self.inner) != 0 }
+    }
+
+    /// Returns the value set by [`set_error_if_exists`](Self::set_error_if_exists).
+    pub fn get_error_if_exists(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_error_if_exists(self.inner) != 0 }
+    }
+
+    /// Returns the value set by [`set_paranoid_checks`](Self::set_paranoid_checks).
+    pub fn get_paranoid_checks(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_paranoid_checks(self.inner) != 0 }
+    }
+
+    /// Returns the value set by [`set_max_open_files`](Self::set_max_open_files).
+    pub fn get_max_open_files(&self) -> c_int {
+        unsafe { ffi::rocksdb_options_get_max_open_files(self.inner) }
+    }
+
+    /// Returns the value set by [`set_max_file_opening_threads`](Self::set_max_file_opening_threads).
+    pub fn get_max_file_opening_threads(&self) -> c_int {
+        unsafe { ffi::rocksdb_options_get_max_file_opening_threads(self.inner) }
+    }
+
+    /// Returns the value set by [`set_max_background_jobs`](Self::set_max_background_jobs).
+    pub fn get_max_background_jobs(&self) -> c_int {
+        unsafe { ffi::rocksdb_options_get_max_background_jobs(self.inner) }
+    }
+
+    /// Returns the value set by [`set_max_subcompactions`](Self::set_max_subcompactions).
+    pub fn get_max_subcompactions(&self) -> u32 {
+        unsafe { ffi::rocksdb_options_get_max_subcompactions(self.inner) }
+    }
+
+    /// Returns the value set by [`set_use_fsync`](Self::set_use_fsync).
+    pub fn get_use_fsync(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_use_fsync(self.inner) != 0 }
+    }
+
+    /// Returns the value set by [`set_bytes_per_sync`](Self::set_bytes_per_sync).
+    pub fn get_bytes_per_sync(&self) -> u64 {
+        unsafe { ffi::rocksdb_options_get_bytes_per_sync(self.inner) }
+    }
+
+    /// Returns the value set by [`set_wal_bytes_per_sync`](Self::set_wal_bytes_per_sync).
+    pub fn get_wal_bytes_per_sync(&self) -> u64 {
+        unsafe { ffi::rocksdb_options_get_wal_bytes_per_sync(self.inner) }
+    }
+
+    /// Returns the value set by [`set_max_total_wal_size`](Self::set_max_total_wal_size).
+    pub fn get_max_total_wal_size(&self) -> u64 {
+        unsafe { ffi::rocksdb_options_get_max_total_wal_size(self.inner) }
+    }
+
+    /// Returns the value set by [`set_db_write_buffer_size`](Self::set_db_write_buffer_size).
+    pub fn get_db_write_buffer_size(&self) -> usize {
+        unsafe { ffi::rocksdb_options_get_db_write_buffer_size(self.inner) }
+    }
+
+    /// Returns the value set by [`set_write_buffer_size`](Self::set_write_buffer_size).
+    pub fn get_write_buffer_size(&self) -> usize {
+        unsafe { ffi::rocksdb_options_get_write_buffer_size(self.inner) }
+    }
+
+    /// Returns the value set by [`set_max_write_buffer_number`](Self::set_max_write_buffer_number).
+    pub fn get_max_write_buffer_number(&self) -> c_int {
+        unsafe { ffi::rocksdb_options_get_max_write_buffer_number(self.inner) }
+    }
+
+    /// Returns the value set by [`set_min_write_buffer_number_to_merge`](Self::set_min_write_buffer_number_to_merge).
+    pub fn get_min_write_buffer_number_to_merge(&self) -> c_int {
+        unsafe { ffi::rocksdb_options_get_min_write_buffer_number_to_merge(self.inner) }
+    }
+
+    /// Returns the value set by [`set_num_levels`](Self::set_num_levels).
+    pub fn get_num_levels(&self) -> c_int {
+        unsafe { ffi::rocksdb_options_get_num_levels(self.inner) }
+    }
+
+    /// Returns the value set by [`set_level_zero_file_num_compaction_trigger`](Self::set_level_zero_file_num_compaction_trigger).
+    pub fn get_level_zero_file_num_compaction_trigger(&self) -> c_int {
+        unsafe { ffi::rocksdb_options_get_level0_file_num_compaction_trigger(self.inner) }
+    }
+
+    /// Returns the value set by [`set_level_zero_slowdown_writes_trigger`](Self::set_level_zero_slowdown_writes_trigger).
+    pub fn get_level_zero_slowdown_writes_trigger(&self) -> c_int {
+        unsafe { ffi::rocksdb_options_get_level0_slowdown_writes_trigger(self.inner) }
+    }
+
+    /// Returns the value set by [`set_level_zero_stop_writes_trigger`](Self::set_level_zero_stop_writes_trigger).
+    pub fn get_level_zero_stop_writes_trigger(&self) -> c_int {
+        unsafe { ffi::rocksdb_options_get_level0_stop_writes_trigger(self.inner) }
+    }
+
+    /// Returns the value set by [`set_target_file_size_base`](Self::set_target_file_size_base).
+    pub fn get_target_file_size_base(&self) -> u64 {
+        unsafe { ffi::rocksdb_options_get_target_file_size_base(self.inner) }
+    }
+
+    /// Returns the value set by [`set_target_file_size_multiplier`](Self::set_target_file_size_multiplier).
+    pub fn get_target_file_size_multiplier(&self) -> i32 {
+        unsafe { ffi::rocksdb_options_get_target_file_size_multiplier(self.inner) }
+    }
+
+    /// Returns the value set by [`set_max_bytes_for_level_base`](Self::set_max_bytes_for_level_base).
+    pub fn get_max_bytes_for_level_base(&self) -> u64 {
+        unsafe { ffi::rocksdb_options_get_max_bytes_for_level_base(self.inner) }
+    }
+
+    /// Returns the value set by [`set_max_bytes_for_level_multiplier`](Self::set_max_bytes_for_level_multiplier).
+    pub fn get_max_bytes_for_level_multiplier(&self) -> f64 {
+        unsafe { ffi::rocksdb_options_get_max_bytes_for_level_multiplier(self.inner) }
+    }
+
+    /// Returns the value set by [`set_level_compaction_dynamic_level_bytes`](Self::set_level_compaction_dynamic_level_bytes).
+    pub fn get_level_compaction_dynamic_level_bytes(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_level_compaction_dynamic_level_bytes(self.inner) != 0 }
+    }
+
+    /// Returns the value set by [`set_max_compaction_bytes`](Self::set_max_compaction_bytes).
+    pub fn get_max_compaction_bytes(&self) -> u64 {
+        unsafe { ffi::rocksdb_options_get_max_compaction_bytes(self.inner) }
+    }
+
+    /// Returns the value set by [`set_compaction_readahead_size`](Self::set_compaction_readahead_size).
+    pub fn get_compaction_readahead_size(&self) -> usize {
+        unsafe { ffi::rocksdb_options_get_compaction_readahead_size(self.inner) }
+    }
+
+    /// Returns the value set by [`set_soft_pending_compaction_bytes_limit`](Self::set_soft_pending_compaction_bytes_limit).
+    pub fn get_soft_pending_compaction_bytes_limit(&self) -> usize {
+        unsafe { ffi::rocksdb_options_get_soft_pending_compaction_bytes_limit(self.inner) }
+    }
+
+    /// Returns the value set by [`set_hard_pending_compaction_bytes_limit`](Self::set_hard_pending_compaction_bytes_limit).
+    pub fn get_hard_pending_compaction_bytes_limit(&self) -> usize {
+        unsafe { ffi::rocksdb_options_get_hard_pending_compaction_bytes_limit(self.inner) }
+    }
+
+    /// Returns the value set by [`set_disable_auto_compactions`](Self::set_disable_auto_compactions).
+    pub fn get_disable_auto_compactions(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_disable_auto_compactions(self.inner) != 0 }
+    }
+
+    /// Returns the value set by [`set_optimize_filters_for_hits`](Self::set_optimize_filters_for_hits).
+    pub fn get_optimize_filters_for_hits(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_optimize_filters_for_hits(self.inner) != 0 }
+    }
+
+    /// Returns the value set by [`set_allow_concurrent_memtable_write`](Self::set_allow_concurrent_memtable_write).
+    pub fn get_allow_concurrent_memtable_write(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_allow_concurrent_memtable_write(self.inner) != 0 }
+    }
+
+    /// Returns the value set by [`set_enable_pipelined_write`](Self::set_enable_pipelined_write).
+    pub fn get_enable_pipelined_write(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_enable_pipelined_write(self.inner) != 0 }
+    }
+
+    /// Returns the value set by [`set_atomic_flush`](Self::set_atomic_flush).
+    pub fn get_atomic_flush(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_atomic_flush(self.inner) != 0 }
+    }
+
+    /// Returns the value set by [`set_use_direct_reads`](Self::set_use_direct_reads).
+    pub fn get_use_direct_reads(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_use_direct_reads(self.inner) != 0 }
+    }
+
+    /// Returns the value set by [`set_use_direct_io_for_flush_and_compaction`](Self::set_use_direct_io_for_flush_and_compaction).
+    pub fn get_use_direct_io_for_flush_and_compaction(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_use_direct_io_for_flush_and_compaction(self.inner) != 0 }
+    }
+
+    /// Returns the value set by [`set_memtable_prefix_bloom_ratio`](Self::set_memtable_prefix_bloom_ratio).
+    pub fn get_memtable_prefix_bloom_ratio(&self) -> f64 {
+        unsafe { ffi::rocksdb_options_get_memtable_prefix_bloom_size_ratio(self.inner) }
+    }
+
+    /// Returns the value set by [`set_max_successive_merges`](Self::set_max_successive_merges).
+    pub fn get_max_successive_merges(&self) -> usize {
+        unsafe { ffi::rocksdb_options_get_max_successive_merges(self.inner) }
+    }
+
+    /// Returns the value set by [`set_inplace_update_support`](Self::set_inplace_update_support).
+    pub fn get_inplace_update_support(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_inplace_update_support(self.inner) != 0 }
+    }
+
+    /// Returns the value set by [`set_arena_block_size`](Self::set_arena_block_size).
+    pub fn get_arena_block_size(&self) -> usize {
+        unsafe { ffi::rocksdb_options_get_arena_block_size(self.inner) }
+    }
+
+    /// Returns the value set by [`set_keep_log_file_num`](Self::set_keep_log_file_num).
+    pub fn get_keep_log_file_num(&self) -> usize {
+        unsafe { ffi::rocksdb_options_get_keep_log_file_num(self.inner) }
+    }
+
+    /// Returns the value set by [`set_max_log_file_size`](Self::set_max_log_file_size).
+    pub fn get_max_log_file_size(&self) -> usize {
+        unsafe { ffi::rocksdb_options_get_max_log_file_size(self.inner) }
+    }
+
+    /// Returns the value set by [`set_stats_dump_period_sec`](Self::set_stats_dump_period_sec).
+    pub fn get_stats_dump_period_sec(&self) -> c_uint {
+        unsafe { ffi::rocksdb_options_get_stats_dump_period_sec(self.inner) }
+    }
+
+    /// Returns the value set by [`set_enable_blob_files`](Self::set_enable_blob_files).
+    pub fn get_enable_blob_files(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_enable_blob_files(self.inner) != 0 }
+    }
+
+    /// Returns the value set by [`set_min_blob_size`](Self::set_min_blob_size).
+    pub fn get_min_blob_size(&self) -> u64 {
+        unsafe { ffi::rocksdb_options_get_min_blob_size(self.inner) }
+    }
+
+    /// Returns the value set by [`set_blob_file_size`](Self::set_blob_file_size).
+    pub fn get_blob_file_size(&self) -> u64 {
+        unsafe { ffi::rocksdb_options_get_blob_file_size(self.inner) }
+    }
+
+    /// Returns the value set by [`set_enable_blob_gc`](Self::set_enable_blob_gc).
+    pub fn get_enable_blob_gc(&self) -> bool {
+        unsafe { ffi::rocksdb_options_get_enable_blob_gc(self.inner) != 0 }
+    }
+
+    /// Returns the value set by [`set_blob_gc_age_cutoff`](Self::set_blob_gc_age_cutoff).
+    pub fn get_blob_gc_age_cutoff(&self) -> f64 {
+        unsafe { ffi::rocksdb_options_get_blob_gc_age_cutoff(self.inner) }
+    }
+
+    /// Returns the blob compression type set by
+    /// [`set_blob_compression_type`](Self::set_blob_compression_type), or
+    /// `None` if it has no [`DBCompressionType`] variant.
+    pub fn get_blob_compression_type(&self) -> Option<DBCompressionType> {
+        DBCompressionType::from_raw(unsafe {
+            ffi::rocksdb_options_get_blob_compression_type(self.inner)
+        })
+    }
+
+    /// Returns the compression type set by
+    /// [`set_bottommost_compression_type`](Self::set_bottommost_compression_type),
+    /// or `None` if it isn't set.
+    pub fn get_bottommost_compression_type(&self) -> Option<DBCompressionType> {
+        DBCompressionType::from_raw(unsafe {
+            ffi::rocksdb_options_get_bottommost_compression(self.inner)
+        })
+    }
+
+    /// Returns the compression type set by
+    /// [`set_compression_type`](Self::set_compression_type), or `None` if it
+    /// has no [`DBCompressionType`] variant.
+    pub fn get_compression_type(&self) -> Option<DBCompressionType> {
+        DBCompressionType::from_raw(unsafe { ffi::rocksdb_options_get_compression(self.inner) })
+    }
+
+    /// Returns the value set by [`set_compaction_style`](Self::set_compaction_style).
+    pub fn get_compaction_style(&self) -> DBCompactionStyle {
+        let style = unsafe { ffi::rocksdb_options_get_compaction_style(self.inner) } as isize;
+        [DBCompactionStyle::Universal, DBCompactionStyle::Fifo]
+            .iter()
+            .copied()
+            .find(|s| *s as isize == style)
+            .unwrap_or(DBCompactionStyle::Level)
+    }
+
+    /// Returns the value set by [`set_wal_recovery_mode`](Self::set_wal_recovery_mode).
+    pub fn get_wal_recovery_mode(&self) -> DBRecoveryMode {
+        let mode = unsafe { ffi::rocksdb_options_get_wal_recovery_mode(self.inner) } as isize;
+        [
+            DBRecoveryMode::TolerateCorruptedTailRecords,
+            DBRecoveryMode::AbsoluteConsistency,
+            DBRecoveryMode::SkipAnyCorruptedRecord,
+        ]
+        .iter()
+        .copied()
+        .find(|m| *m as isize == mode)
+        .unwrap_or(DBRecoveryMode::PointInTime)
+    }
+
+    /// Returns the value set by [`set_info_log_level`](Self::set_info_log_level).
+    pub fn get_info_log_level(&self) -> LogLevel {
+        let level = unsafe { ffi::rocksdb_options_get_info_log_level(self.inner) };
+        [
+            LogLevel::Debug,
+            LogLevel::Warn,
+            LogLevel::Error,
+            LogLevel::Fatal,
+            LogLevel::Header,
+        ]
+        .iter()
+        .copied()
+        .find(|l| *l as c_int == level)
+        .unwrap_or(LogLevel::Info)
+    }
 }
 
 impl Default for Options {
@@ -2926,6 +3790,7 @@ impl Default for Options {
             Self {
                 inner: opts,
                 outlive: OptionsMustOutliveDB::default(),
+                names: OptionsNames::default(),
             }
         }
     }
@@ -3334,6 +4199,20 @@ pub enum BlockBasedIndexType {
     TwoLevelIndexSearch,
 }
 
+/// Used by BlockBasedOptions::set_filter_policy.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum FilterPolicy {
+    /// A bloom filter covering a whole SST file, or one partition of it when
+    /// partitioned filters are enabled. Around 10 bits per key give a 1%
+    /// false positive rate.
+    Bloom { bits_per_key: c_int },
+
+    /// The legacy bloom filter built for every data block. It can't be
+    /// partitioned and uses more CPU than [`FilterPolicy::Bloom`].
+    BlockBasedBloom { bits_per_key: c_int },
+}
+
 /// Used by BlockBasedOptions::set_data_block_index_type.
 #[repr(C)]
 pub enum DataBlockIndexType {
@@ -3388,6 +4267,23 @@ pub enum DBCompressionType {
     Zstd = ffi::rocksdb_zstd_compression as isize,
 }
 
+impl DBCompressionType {
+    pub(crate) fn from_raw(compression: c_int) -> Option<Self> {
+        [
+            Self::None,
+            Self::Snappy,
+            Self::Zlib,
+            Self::Bz2,
+            Self::Lz4,
+            Self::Lz4hc,
+            Self::Zstd,
+        ]
+        .iter()
+        .copied()
+        .find(|t| *t as c_int == compression)
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum DBCompactionStyle {
     Level = ffi::rocksdb_level_compaction as isize,
@@ -3403,6 +4299,18 @@ pub enum DBRecoveryMode {
     SkipAnyCorruptedRecord = ffi::rocksdb_skip_any_corrupted_records_recovery as isize,
 }
 
+/// Severity of the messages written to the info LOG
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(i32)]
+pub enum LogLevel {
+    Debug = 0,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+    Header,
+}
+
 /// File access pattern once a compaction has started
 #[derive(Debug, Copy, Clone, PartialEq)]
 #[repr(i32)]
@@ -3666,7 +4574,8 @@ impl Drop for DBPath {
 
 #[cfg(test)]
 mod tests {
-    use crate::{MemtableFactory, Options};
+    use crate::{MemtableFactory, Options, RateLimiter, DB};
+    use std::sync::Arc;
 
     #[test]
     fn test_enable_statistics() {
@@ -3701,4 +4610,25 @@ mod tests {
         let opts = Options::default();
         assert!(opts.get_statistics().is_none());
     }
+
+    #[test]
+    fn test_rate_limiter_shared_by_databases() {
+        let dir_a = tempfile::tempdir().unwrap();
+        let dir_b = tempfile::tempdir().unwrap();
+        let limiter = RateLimiter::new(1 << 20, 100 * 1000, 10).unwrap();
+
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_rate_limiter(&limiter);
+        let db_a = DB::open(&opts, dir_a.path()).unwrap();
+        let db_b = DB::open(&opts, dir_b.path()).unwrap();
+        drop(opts);
+        // Held by `limiter` and both databases.
+        assert_eq!(Arc::strong_count(&limiter.0), 3);
+
+        drop(db_a);
+        assert_eq!(Arc::strong_count(&limiter.0), 2);
+        drop(db_b);
+        assert_eq!(Arc::strong_count(&limiter.0), 1);
+    }
 }
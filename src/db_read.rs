@@ -0,0 +1,805 @@
+// Copyright 2021 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    db::{AccessType, DBWithThreadMode, MultiThreaded, SingleThreaded, ThreadMode},
+    properties::Property,
+    AsColumnFamilyRef, BoundColumnFamily, ColumnFamily, ColumnFamilyDescriptor,
+    DBIteratorWithThreadMode, DBPinnableSlice, DBRawIteratorWithThreadMode, Error, IteratorMode,
+    Options, ReadOptions,
+};
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A type alias to the read-only handle with the thread mode of [`DB`](crate::DB).
+#[cfg(not(feature = "multi-threaded-cf"))]
+pub type ReadOnlyDB = ReadOnlyDBWithThreadMode<SingleThreaded>;
+
+#[cfg(feature = "multi-threaded-cf")]
+pub type ReadOnlyDB = ReadOnlyDBWithThreadMode<MultiThreaded>;
+
+/// A type alias to the secondary handle with the thread mode of [`DB`](crate::DB).
+#[cfg(not(feature = "multi-threaded-cf"))]
+pub type SecondaryDB = SecondaryDBWithThreadMode<SingleThreaded>;
+
+#[cfg(feature = "multi-threaded-cf")]
+pub type SecondaryDB = SecondaryDBWithThreadMode<MultiThreaded>;
+
+pub(crate) mod private {
+    pub trait Sealed {
+        type ThreadMode: crate::ThreadMode;
+
+        fn inner_db(&self) -> &crate::DBWithThreadMode<Self::ThreadMode>;
+    }
+}
+
+/// Read operations shared by [`DBWithThreadMode`], [`ReadOnlyDBWithThreadMode`]
+/// and [`SecondaryDBWithThreadMode`].
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait DBRead: private::Sealed {
+    /// Returns the path to the database directory.
+    fn path(&self) -> &Path {
+        self.inner_db().path()
+    }
+
+    /// Returns the bytes associated with a key value with read options.
+    fn get_opt<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        readopts: &ReadOptions,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        self.inner_db().get_opt(key, readopts)
+    }
+
+    /// Returns the bytes associated with a key value.
+    fn get<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<Vec<u8>>, Error> {
+        self.inner_db().get(key)
+    }
+
+    /// Returns the bytes associated with a key value and the given column family with read
+    /// options.
+    fn get_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        readopts: &ReadOptions,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        self.inner_db().get_cf_opt(cf, key, readopts)
+    }
+
+    /// Returns the bytes associated with a key value and the given column family.
+    fn get_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        self.inner_db().get_cf(cf, key)
+    }
+
+    /// Returns the value associated with a key using RocksDB's PinnableSlice
+    /// so as to avoid unnecessary memory copy.
+    fn get_pinned_opt<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        readopts: &ReadOptions,
+    ) -> Result<Option<DBPinnableSlice>, Error> {
+        self.inner_db().get_pinned_opt(key, readopts)
+    }
+
+    /// Returns the value associated with a key using RocksDB's PinnableSlice
+    /// so as to avoid unnecessary memory copy, with default read options.
+    fn get_pinned<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<DBPinnableSlice>, Error> {
+        self.inner_db().get_pinned(key)
+    }
+
+    /// Returns the value associated with a key and the given column family
+    /// using RocksDB's PinnableSlice so as to avoid unnecessary memory copy.
+    fn get_pinned_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        readopts: &ReadOptions,
+    ) -> Result<Option<DBPinnableSlice>, Error> {
+        self.inner_db().get_pinned_cf_opt(cf, key, readopts)
+    }
+
+    /// Returns the value associated with a key and the given column family
+    /// using RocksDB's PinnableSlice, with default read options.
+    fn get_pinned_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+    ) -> Result<Option<DBPinnableSlice>, Error> {
+        self.inner_db().get_pinned_cf(cf, key)
+    }
+
+    /// Copies the value associated with a key into `value`, reusing its allocation, and returns
+    /// whether the key was found.
+    fn get_into_opt<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        readopts: &ReadOptions,
+        value: &mut Vec<u8>,
+    ) -> Result<bool, Error> {
+        self.inner_db().get_into_opt(key, readopts, value)
+    }
+
+    /// Copies the value associated with a key into `value`, with default read options.
+    fn get_into<K: AsRef<[u8]>>(&self, key: K, value: &mut Vec<u8>) -> Result<bool, Error> {
+        self.inner_db().get_into(key, value)
+    }
+
+    /// Copies the value associated with a key and the given column family into `value`, reusing
+    /// its allocation, and returns whether the key was found.
+    fn get_into_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        readopts: &ReadOptions,
+        value: &mut Vec<u8>,
+    ) -> Result<bool, Error> {
+        self.inner_db().get_into_cf_opt(cf, key, readopts, value)
+    }
+
+    /// Copies the value associated with a key and the given column family into `value`, with
+    /// default read options.
+    fn get_into_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        value: &mut Vec<u8>,
+    ) -> Result<bool, Error> {
+        self.inner_db().get_into_cf(cf, key, value)
+    }
+
+    /// Calls `f` with the value associated with a key, without copying it.
+    fn get_with_opt<K, F, R>(
+        &self,
+        key: K,
+        readopts: &ReadOptions,
+        f: F,
+    ) -> Result<Option<R>, Error>
+    where
+        K: AsRef<[u8]>,
+        F: FnOnce(&[u8]) -> R,
+    {
+        self.inner_db().get_with_opt(key, readopts, f)
+    }
+
+    /// Calls `f` with the value associated with a key, with default read options.
+    fn get_with<K, F, R>(&self, key: K, f: F) -> Result<Option<R>, Error>
+    where
+        K: AsRef<[u8]>,
+        F: FnOnce(&[u8]) -> R,
+    {
+        self.inner_db().get_with(key, f)
+    }
+
+    /// Calls `f` with the value associated with a key and the given column family, without
+    /// copying it.
+    fn get_with_cf_opt<K, F, R>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        readopts: &ReadOptions,
+        f: F,
+    ) -> Result<Option<R>, Error>
+    where
+        K: AsRef<[u8]>,
+        F: FnOnce(&[u8]) -> R,
+    {
+        self.inner_db().get_with_cf_opt(cf, key, readopts, f)
+    }
+
+    /// Calls `f` with the value associated with a key and the given column family, with default
+    /// read options.
+    fn get_with_cf<K, F, R>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        f: F,
+    ) -> Result<Option<R>, Error>
+    where
+        K: AsRef<[u8]>,
+        F: FnOnce(&[u8]) -> R,
+    {
+        self.inner_db().get_with_cf(cf, key, f)
+    }
+
+    /// Returns the values associated with the given keys.
+    fn multi_get<K, I>(&self, keys: I) -> Vec<Result<Option<Vec<u8>>, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        self.inner_db().multi_get(keys)
+    }
+
+    /// Returns the values associated with the given keys using read options.
+    fn multi_get_opt<K, I>(
+        &self,
+        keys: I,
+        readopts: &ReadOptions,
+    ) -> Vec<Result<Option<Vec<u8>>, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        self.inner_db().multi_get_opt(keys, readopts)
+    }
+
+    /// Returns the values associated with the given keys and column families.
+    fn multi_get_cf<'a, 'b: 'a, K, I, W: 'b>(
+        &'a self,
+        keys: I,
+    ) -> Vec<Result<Option<Vec<u8>>, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = (&'b W, K)>,
+        W: AsColumnFamilyRef,
+    {
+        self.inner_db().multi_get_cf(keys)
+    }
+
+    /// Returns the values associated with the given keys and column families using read options.
+    fn multi_get_cf_opt<'a, 'b: 'a, K, I, W: 'b>(
+        &'a self,
+        keys: I,
+        readopts: &ReadOptions,
+    ) -> Vec<Result<Option<Vec<u8>>, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = (&'b W, K)>,
+        W: AsColumnFamilyRef,
+    {
+        self.inner_db().multi_get_cf_opt(keys, readopts)
+    }
+
+    /// Copies the values associated with the given keys into `values`, reusing the allocations
+    /// of its elements, and returns whether each key was found.
+    fn multi_get_into<K, I>(&self, keys: I, values: &mut Vec<Vec<u8>>) -> Vec<Result<bool, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        self.inner_db().multi_get_into(keys, values)
+    }
+
+    /// Copies the values associated with the given keys into `values` using read options.
+    fn multi_get_into_opt<K, I>(
+        &self,
+        keys: I,
+        readopts: &ReadOptions,
+        values: &mut Vec<Vec<u8>>,
+    ) -> Vec<Result<bool, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        self.inner_db().multi_get_into_opt(keys, readopts, values)
+    }
+
+    /// Copies the values associated with the given keys and column families into `values`.
+    fn multi_get_into_cf<'a, 'b: 'a, K, I, W: 'b>(
+        &'a self,
+        keys: I,
+        values: &mut Vec<Vec<u8>>,
+    ) -> Vec<Result<bool, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = (&'b W, K)>,
+        W: AsColumnFamilyRef,
+    {
+        self.inner_db().multi_get_into_cf(keys, values)
+    }
+
+    /// Copies the values associated with the given keys and column families into `values` using
+    /// read options.
+    fn multi_get_into_cf_opt<'a, 'b: 'a, K, I, W: 'b>(
+        &'a self,
+        keys: I,
+        readopts: &ReadOptions,
+        values: &mut Vec<Vec<u8>>,
+    ) -> Vec<Result<bool, Error>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = (&'b W, K)>,
+        W: AsColumnFamilyRef,
+    {
+        self.inner_db()
+            .multi_get_into_cf_opt(keys, readopts, values)
+    }
+
+    /// Calls `f` with the index and the value of each of the given keys, without copying the
+    /// values.
+    fn multi_get_with<K, I, F>(&self, keys: I, f: F)
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+        F: FnMut(usize, Result<Option<&[u8]>, Error>),
+    {
+        self.inner_db().multi_get_with(keys, f);
+    }
+
+    /// Calls `f` with the index and the value of each of the given keys using read options.
+    fn multi_get_with_opt<K, I, F>(&self, keys: I, readopts: &ReadOptions, f: F)
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+        F: FnMut(usize, Result<Option<&[u8]>, Error>),
+    {
+        self.inner_db().multi_get_with_opt(keys, readopts, f);
+    }
+
+    /// Calls `f` with the index and the value of each of the given keys and column families.
+    fn multi_get_with_cf<'a, 'b: 'a, K, I, W: 'b, F>(&'a self, keys: I, f: F)
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = (&'b W, K)>,
+        W: AsColumnFamilyRef,
+        F: FnMut(usize, Result<Option<&[u8]>, Error>),
+    {
+        self.inner_db().multi_get_with_cf(keys, f);
+    }
+
+    /// Calls `f` with the index and the value of each of the given keys and column families using
+    /// read options.
+    fn multi_get_with_cf_opt<'a, 'b: 'a, K, I, W: 'b, F>(
+        &'a self,
+        keys: I,
+        readopts: &ReadOptions,
+        f: F,
+    ) where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = (&'b W, K)>,
+        W: AsColumnFamilyRef,
+        F: FnMut(usize, Result<Option<&[u8]>, Error>),
+    {
+        self.inner_db().multi_get_with_cf_opt(keys, readopts, f);
+    }
+
+    /// Returns `false` if the given key definitely doesn't exist in the database, otherwise returns
+    /// `true`.
+    fn key_may_exist<K: AsRef<[u8]>>(&self, key: K) -> bool {
+        self.inner_db().key_may_exist(key)
+    }
+
+    /// Returns `false` if the given key definitely doesn't exist in the specified column family,
+    /// otherwise returns `true`.
+    fn key_may_exist_cf<K: AsRef<[u8]>>(&self, cf: &impl AsColumnFamilyRef, key: K) -> bool {
+        self.inner_db().key_may_exist_cf(cf, key)
+    }
+
+    /// Opens an iterator over the database, using the default read options.
+    fn iterator(
+        &self,
+        mode: IteratorMode,
+    ) -> DBIteratorWithThreadMode<'_, DBWithThreadMode<Self::ThreadMode>> {
+        self.inner_db().iterator(mode)
+    }
+
+    /// Opens an iterator over the database, using the given read options.
+    fn iterator_opt(
+        &self,
+        mode: IteratorMode,
+        readopts: ReadOptions,
+    ) -> DBIteratorWithThreadMode<'_, DBWithThreadMode<Self::ThreadMode>> {
+        self.inner_db().iterator_opt(mode, readopts)
+    }
+
+    /// Opens an iterator over the given column family, using the default read options.
+    fn iterator_cf(
+        &self,
+        cf_handle: &impl AsColumnFamilyRef,
+        mode: IteratorMode,
+    ) -> DBIteratorWithThreadMode<'_, DBWithThreadMode<Self::ThreadMode>> {
+        self.inner_db().iterator_cf(cf_handle, mode)
+    }
+
+    /// Opens an iterator over the given column family, using the given read options.
+    fn iterator_cf_opt(
+        &self,
+        cf_handle: &impl AsColumnFamilyRef,
+        readopts: ReadOptions,
+        mode: IteratorMode,
+    ) -> DBIteratorWithThreadMode<'_, DBWithThreadMode<Self::ThreadMode>> {
+        self.inner_db().iterator_cf_opt(cf_handle, readopts, mode)
+    }
+
+    /// Opens an iterator over the keys starting with `prefix`.
+    fn prefix_iterator<P: AsRef<[u8]>>(
+        &self,
+        prefix: P,
+    ) -> DBIteratorWithThreadMode<'_, DBWithThreadMode<Self::ThreadMode>> {
+        self.inner_db().prefix_iterator(prefix)
+    }
+
+    /// Opens an iterator over the keys of the given column family starting with `prefix`.
+    fn prefix_iterator_cf<P: AsRef<[u8]>>(
+        &self,
+        cf_handle: &impl AsColumnFamilyRef,
+        prefix: P,
+    ) -> DBIteratorWithThreadMode<'_, DBWithThreadMode<Self::ThreadMode>> {
+        self.inner_db().prefix_iterator_cf(cf_handle, prefix)
+    }
+
+    /// Opens a raw iterator over the database, using the default read options.
+    fn raw_iterator(&self) -> DBRawIteratorWithThreadMode<'_, DBWithThreadMode<Self::ThreadMode>> {
+        self.inner_db().raw_iterator()
+    }
+
+    /// Opens a raw iterator over the database, using the given read options.
+    fn raw_iterator_opt(
+        &self,
+        readopts: ReadOptions,
+    ) -> DBRawIteratorWithThreadMode<'_, DBWithThreadMode<Self::ThreadMode>> {
+        self.inner_db().raw_iterator_opt(readopts)
+    }
+
+    /// Opens a raw iterator over the given column family, using the default read options.
+    fn raw_iterator_cf(
+        &self,
+        cf_handle: &impl AsColumnFamilyRef,
+    ) -> DBRawIteratorWithThreadMode<'_, DBWithThreadMode<Self::ThreadMode>> {
+        self.inner_db().raw_iterator_cf(cf_handle)
+    }
+
+    /// Opens a raw iterator over the given column family, using the given read options.
+    fn raw_iterator_cf_opt(
+        &self,
+        cf_handle: &impl AsColumnFamilyRef,
+        readopts: ReadOptions,
+    ) -> DBRawIteratorWithThreadMode<'_, DBWithThreadMode<Self::ThreadMode>> {
+        self.inner_db().raw_iterator_cf_opt(cf_handle, readopts)
+    }
+
+    /// Retrieves a RocksDB property by name.
+    fn property_value(&self, name: impl AsRef<str>) -> Result<Option<String>, Error> {
+        self.inner_db().property_value(name)
+    }
+
+    /// Retrieves a RocksDB property by name, for a specific column family.
+    fn property_value_cf(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        name: impl AsRef<str>,
+    ) -> Result<Option<String>, Error> {
+        self.inner_db().property_value_cf(cf, name)
+    }
+
+    /// Retrieves a RocksDB property and casts it to an integer.
+    fn property_int_value(&self, name: impl AsRef<str>) -> Result<Option<u64>, Error> {
+        self.inner_db().property_int_value(name)
+    }
+
+    /// Retrieves a RocksDB property for a specific column family and casts it to an integer.
+    fn property_int_value_cf(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        name: impl AsRef<str>,
+    ) -> Result<Option<u64>, Error> {
+        self.inner_db().property_int_value_cf(cf, name)
+    }
+
+    /// Retrieves a RocksDB property made of `name=value` pairs and splits it into a map.
+    fn property_map_value(
+        &self,
+        name: impl AsRef<str>,
+    ) -> Result<Option<HashMap<String, String>>, Error> {
+        self.inner_db().property_map_value(name)
+    }
+
+    /// Retrieves a RocksDB property made of `name=value` pairs for a specific
+    /// column family, and splits it into a map.
+    fn property_map_value_cf(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        name: impl AsRef<str>,
+    ) -> Result<Option<HashMap<String, String>>, Error> {
+        self.inner_db().property_map_value_cf(cf, name)
+    }
+
+    /// Retrieves an integer property, rejecting properties of other types.
+    fn int_property(&self, property: &Property) -> Result<Option<u64>, Error> {
+        self.inner_db().int_property(property)
+    }
+
+    /// Retrieves an integer property for a specific column family, rejecting
+    /// properties of other types.
+    fn int_property_cf(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        property: &Property,
+    ) -> Result<Option<u64>, Error> {
+        self.inner_db().int_property_cf(cf, property)
+    }
+
+    /// Retrieves a free-form string property, rejecting properties of other
+    /// types.
+    fn string_property(&self, property: &Property) -> Result<Option<String>, Error> {
+        self.inner_db().string_property(property)
+    }
+
+    /// Retrieves a free-form string property for a specific column family,
+    /// rejecting properties of other types.
+    fn string_property_cf(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        property: &Property,
+    ) -> Result<Option<String>, Error> {
+        self.inner_db().string_property_cf(cf, property)
+    }
+
+    /// Retrieves a property made of `name=value` pairs and splits it into a
+    /// map, rejecting properties of other types.
+    fn map_property(&self, property: &Property) -> Result<Option<HashMap<String, String>>, Error> {
+        self.inner_db().map_property(property)
+    }
+
+    /// Retrieves a property made of `name=value` pairs for a specific column
+    /// family and splits it into a map, rejecting properties of other types.
+    fn map_property_cf(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        property: &Property,
+    ) -> Result<Option<HashMap<String, String>>, Error> {
+        self.inner_db().map_property_cf(cf, property)
+    }
+
+    /// The sequence number of the most recent transaction.
+    fn latest_sequence_number(&self) -> u64 {
+        self.inner_db().latest_sequence_number()
+    }
+}
+
+impl<T: ThreadMode> private::Sealed for DBWithThreadMode<T> {
+    type ThreadMode = T;
+
+    fn inner_db(&self) -> &DBWithThreadMode<T> {
+        self
+    }
+}
+
+impl<T: ThreadMode> DBRead for DBWithThreadMode<T> {}
+
+/// A database opened for read only.
+///
+/// Only the read operations of [`DBRead`] are available, so writes are rejected
+/// at compile time instead of failing at runtime.
+///
+/// ```
+/// use rocksdb::{DBRead, Options, ReadOnlyDB, DB};
+///
+/// let path = "_path_for_rocksdb_storage_read_only";
+/// {
+///     let db = DB::open_default(path).unwrap();
+///     db.put(b"key", b"value").unwrap();
+/// }
+/// {
+///     let db = ReadOnlyDB::open(&Options::default(), path, false).unwrap();
+///     assert_eq!(db.get(b"key").unwrap().unwrap(), b"value");
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+pub struct ReadOnlyDBWithThreadMode<T: ThreadMode> {
+    db: DBWithThreadMode<T>,
+}
+
+impl<T: ThreadMode> ReadOnlyDBWithThreadMode<T> {
+    /// Opens the database for read only with the specified options.
+    pub fn open<P: AsRef<Path>>(
+        opts: &Options,
+        path: P,
+        error_if_log_file_exist: bool,
+    ) -> Result<Self, Error> {
+        Self::open_cf(opts, path, None::<&str>, error_if_log_file_exist)
+    }
+
+    /// Opens the database for read only with the given database options and column family names.
+    ///
+    /// Column families opened using this function will be opened with default `Options`.
+    pub fn open_cf<P, I, N>(
+        opts: &Options,
+        path: P,
+        cfs: I,
+        error_if_log_file_exist: bool,
+    ) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = N>,
+        N: AsRef<str>,
+    {
+        let cfs = cfs
+            .into_iter()
+            .map(|name| ColumnFamilyDescriptor::new(name.as_ref(), Options::default()));
+
+        Self::open_cf_descriptors(opts, path, cfs, error_if_log_file_exist)
+    }
+
+    /// Opens the database for read only with the given database options and column family
+    /// descriptors.
+    pub fn open_cf_descriptors<P, I>(
+        opts: &Options,
+        path: P,
+        cfs: I,
+        error_if_log_file_exist: bool,
+    ) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = ColumnFamilyDescriptor>,
+    {
+        let db = DBWithThreadMode::open_cf_descriptors_internal(
+            opts,
+            path,
+            cfs,
+            &AccessType::ReadOnly {
+                error_if_log_file_exist,
+            },
+        )?;
+        Ok(Self { db })
+    }
+}
+
+impl ReadOnlyDBWithThreadMode<SingleThreaded> {
+    /// Returns the underlying column family handle.
+    pub fn cf_handle(&self, name: &str) -> Option<&ColumnFamily> {
+        self.db.cf_handle(name)
+    }
+}
+
+impl ReadOnlyDBWithThreadMode<MultiThreaded> {
+    /// Returns the underlying column family handle.
+    pub fn cf_handle(&self, name: &str) -> Option<Arc<BoundColumnFamily>> {
+        self.db.cf_handle(name)
+    }
+}
+
+impl<T: ThreadMode> private::Sealed for ReadOnlyDBWithThreadMode<T> {
+    type ThreadMode = T;
+
+    fn inner_db(&self) -> &DBWithThreadMode<T> {
+        &self.db
+    }
+}
+
+impl<T: ThreadMode> DBRead for ReadOnlyDBWithThreadMode<T> {}
+
+impl<T: ThreadMode> fmt::Debug for ReadOnlyDBWithThreadMode<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Read-only RocksDB {{ path: {:?} }}", self.db.path())
+    }
+}
+
+/// A database opened as a secondary instance of a primary database.
+///
+/// Besides the read operations of [`DBRead`], only
+/// [`try_catch_up_with_primary`](Self::try_catch_up_with_primary) is available.
+///
+/// ```
+/// use rocksdb::{DBRead, Options, SecondaryDB, DB};
+///
+/// let primary_path = "_path_for_rocksdb_storage_secondary_primary";
+/// let secondary_path = "_path_for_rocksdb_storage_secondary_secondary";
+/// {
+///     let primary = DB::open_default(primary_path).unwrap();
+///     let mut opts = Options::default();
+///     opts.set_max_open_files(-1);
+///     let secondary = SecondaryDB::open(&opts, primary_path, secondary_path).unwrap();
+///
+///     primary.put(b"key", b"value").unwrap();
+///     primary.flush().unwrap();
+///     secondary.try_catch_up_with_primary().unwrap();
+///     assert_eq!(secondary.get(b"key").unwrap().unwrap(), b"value");
+/// }
+/// let _ = DB::destroy(&Options::default(), primary_path);
+/// let _ = DB::destroy(&Options::default(), secondary_path);
+/// ```
+pub struct SecondaryDBWithThreadMode<T: ThreadMode> {
+    db: DBWithThreadMode<T>,
+}
+
+impl<T: ThreadMode> SecondaryDBWithThreadMode<T> {
+    /// Opens the database as a secondary.
+    pub fn open<P: AsRef<Path>>(
+        opts: &Options,
+        primary_path: P,
+        secondary_path: P,
+    ) -> Result<Self, Error> {
+        Self::open_cf(opts, primary_path, secondary_path, None::<&str>)
+    }
+
+    /// Opens the database as a secondary with the given database options and column family names.
+    ///
+    /// Column families opened using this function will be opened with default `Options`.
+    pub fn open_cf<P, I, N>(
+        opts: &Options,
+        primary_path: P,
+        secondary_path: P,
+        cfs: I,
+    ) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = N>,
+        N: AsRef<str>,
+    {
+        let cfs = cfs
+            .into_iter()
+            .map(|name| ColumnFamilyDescriptor::new(name.as_ref(), Options::default()));
+
+        Self::open_cf_descriptors(opts, primary_path, secondary_path, cfs)
+    }
+
+    /// Opens the database as a secondary with the given database options and column family
+    /// descriptors.
+    pub fn open_cf_descriptors<P, I>(
+        opts: &Options,
+        primary_path: P,
+        secondary_path: P,
+        cfs: I,
+    ) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = ColumnFamilyDescriptor>,
+    {
+        let db = DBWithThreadMode::open_cf_descriptors_internal(
+            opts,
+            primary_path,
+            cfs,
+            &AccessType::Secondary {
+                secondary_path: secondary_path.as_ref(),
+            },
+        )?;
+        Ok(Self { db })
+    }
+
+    /// Tries to catch up with the primary by reading as much as possible from the
+    /// log files.
+    pub fn try_catch_up_with_primary(&self) -> Result<(), Error> {
+        self.db.try_catch_up_with_primary()
+    }
+}
+
+impl SecondaryDBWithThreadMode<SingleThreaded> {
+    /// Returns the underlying column family handle.
+    pub fn cf_handle(&self, name: &str) -> Option<&ColumnFamily> {
+        self.db.cf_handle(name)
+    }
+}
+
+impl SecondaryDBWithThreadMode<MultiThreaded> {
+    /// Returns the underlying column family handle.
+    pub fn cf_handle(&self, name: &str) -> Option<Arc<BoundColumnFamily>> {
+        self.db.cf_handle(name)
+    }
+}
+
+impl<T: ThreadMode> private::Sealed for SecondaryDBWithThreadMode<T> {
+    type ThreadMode = T;
+
+    fn inner_db(&self) -> &DBWithThreadMode<T> {
+        &self.db
+    }
+}
+
+impl<T: ThreadMode> DBRead for SecondaryDBWithThreadMode<T> {}
+
+impl<T: ThreadMode> fmt::Debug for SecondaryDBWithThreadMode<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Secondary RocksDB {{ path: {:?} }}", self.db.path())
+    }
+}
@@ -79,6 +79,7 @@
 mod ffi_util;
 
 pub mod backup;
+mod bulk_load;
 pub mod checkpoint;
 mod column_family;
 pub mod compaction_filter;
@@ -88,38 +89,52 @@ mod db;
 mod db_iterator;
 mod db_options;
 mod db_pinnable_slice;
+mod db_read;
 pub mod merge_operator;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+mod options_file;
+mod parallel_scan;
 pub mod perf;
+pub mod properties;
+pub mod secondary;
 mod slice_transform;
 mod snapshot;
 mod sst_file_writer;
 mod write_batch;
 
 pub use crate::{
+    bulk_load::{BulkLoadOptions, BulkLoadSummary, BulkLoader, DuplicateKeyPolicy},
     column_family::{
         AsColumnFamilyRef, BoundColumnFamily, ColumnFamily, ColumnFamilyDescriptor,
-        ColumnFamilyRef, DEFAULT_COLUMN_FAMILY_NAME,
+        ColumnFamilyRef, ColumnFamilyTtl, DEFAULT_COLUMN_FAMILY_NAME,
     },
     compaction_filter::Decision as CompactionDecision,
-    db::{DBWithThreadMode, LiveFile, MultiThreaded, SingleThreaded, ThreadMode, DB},
+    db::{DBWithThreadMode, LiveFile, MultiThreaded, Range, SingleThreaded, ThreadMode, DB},
     db_iterator::{
         DBIterator, DBIteratorWithThreadMode, DBRawIterator, DBRawIteratorWithThreadMode,
-        DBWALIterator, Direction, IteratorMode,
+        DBWALIterator, Direction, IteratorMode, OwnedIterator, OwnedRawIterator,
     },
     db_options::{
         BlockBasedIndexType, BlockBasedOptions, BottommostLevelCompaction, Cache, CompactOptions,
         CuckooTableOptions, DBCompactionStyle, DBCompressionType, DBPath, DBRecoveryMode,
-        DataBlockIndexType, Env, FifoCompactOptions, FlushOptions, IngestExternalFileOptions,
-        MemtableFactory, Options, PlainTableFactoryOptions, ReadOptions, UniversalCompactOptions,
-        UniversalCompactionStopStyle, WriteOptions,
+        DataBlockIndexType, Env, FifoCompactOptions, FilterPolicy, FlushOptions,
+        IngestExternalFileOptions, LogLevel, MemtableFactory, Options, PlainTableFactoryOptions,
+        RateLimiter, ReadOptions, UniversalCompactOptions, UniversalCompactionStopStyle,
+        WriteOptions,
     },
     db_pinnable_slice::DBPinnableSlice,
+    db_read::{
+        DBRead, ReadOnlyDB, ReadOnlyDBWithThreadMode, SecondaryDB, SecondaryDBWithThreadMode,
+    },
     merge_operator::MergeOperands,
+    options_file::{OptionDiff, OptionsLoader},
+    parallel_scan::{KeyRange, ParallelScanner, ShardIterator},
     perf::{PerfContext, PerfMetric, PerfStatsLevel},
     slice_transform::SliceTransform,
-    snapshot::{Snapshot, SnapshotWithThreadMode},
-    sst_file_writer::SstFileWriter,
-    write_batch::{WriteBatch, WriteBatchIterator},
+    snapshot::{OwnedSnapshot, Snapshot, SnapshotWithThreadMode},
+    sst_file_writer::{ExternalSstFileInfo, SstFileWriter},
+    write_batch::{WriteBatch, WriteBatchIterator, WriteBatchIteratorCf},
 };
 
 use librocksdb_sys as ffi;
@@ -172,9 +187,9 @@ impl fmt::Display for Error {
 mod test {
     use super::{
         BlockBasedOptions, BoundColumnFamily, Cache, ColumnFamily, ColumnFamilyDescriptor,
-        DBIterator, DBRawIterator, Env, IngestExternalFileOptions, Options,
-        PlainTableFactoryOptions, ReadOptions, Snapshot, SstFileWriter, WriteBatch, WriteOptions,
-        DB,
+        DBIterator, DBRawIterator, Env, IngestExternalFileOptions, MultiThreaded, Options,
+        OwnedIterator, OwnedRawIterator, OwnedSnapshot, PlainTableFactoryOptions, RateLimiter,
+        ReadOptions, SingleThreaded, Snapshot, SstFileWriter, WriteBatch, WriteOptions, DB,
     };
 
     #[test]
@@ -190,6 +205,12 @@ mod test {
         is_send::<DBIterator<'_>>();
         is_send::<DBRawIterator<'_>>();
         is_send::<Snapshot>();
+        is_send::<OwnedSnapshot<SingleThreaded>>();
+        is_send::<OwnedIterator<SingleThreaded>>();
+        is_send::<OwnedRawIterator<SingleThreaded>>();
+        is_send::<OwnedSnapshot<MultiThreaded>>();
+        is_send::<OwnedIterator<MultiThreaded>>();
+        is_send::<OwnedRawIterator<MultiThreaded>>();
         is_send::<Options>();
         is_send::<ReadOptions>();
         is_send::<WriteOptions>();
@@ -203,6 +224,7 @@ mod test {
         is_send::<WriteBatch>();
         is_send::<Cache>();
         is_send::<Env>();
+        is_send::<RateLimiter>();
     }
 
     #[test]
@@ -215,6 +237,7 @@ mod test {
 
         is_sync::<DB>();
         is_sync::<Snapshot>();
+        is_sync::<OwnedSnapshot<SingleThreaded>>();
         is_sync::<Options>();
         is_sync::<ReadOptions>();
         is_sync::<WriteOptions>();
@@ -225,5 +248,6 @@ mod test {
         is_sync::<SstFileWriter>();
         is_sync::<Cache>();
         is_sync::<Env>();
+        is_sync::<RateLimiter>();
     }
 }
@@ -0,0 +1,557 @@
+// Copyright 2021 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Renders database properties, statistics and memory usage in the
+//! [Prometheus text exposition format](https://prometheus.io/docs/instrumenting/exposition_formats/).
+//!
+//! Enabled with the `metrics` feature.
+//!
+//! ```
+//! use rocksdb::{metrics::MetricsRenderer, Options, DB};
+//!
+//! let path = "_path_for_rocksdb_storage_metrics";
+//! {
+//!     let mut opts = Options::default();
+//!     opts.create_if_missing(true);
+//!     opts.enable_statistics();
+//!     let db = DB::open(&opts, path).unwrap();
+//!
+//!     let mut renderer = MetricsRenderer::new();
+//!     renderer.add_label("service", "example");
+//!     renderer.add_db_with_statistics(&db, &opts);
+//!     let text = renderer.render().unwrap();
+//!     assert!(text.contains("rocksdb_estimate_num_keys{"));
+//!     assert!(text.contains("rocksdb_block_cache_miss_total{"));
+//! }
+//! let _ = DB::destroy(&Options::default(), path);
+//! ```
+
+use crate::{
+    db_read::private::Sealed as _,
+    perf::get_db_memory_usage_stats,
+    properties::{self, Property, NUM_FILES_AT_LEVEL_PREFIX},
+    Cache, DBWithThreadMode, Error, MultiThreaded, Options, ReadOnlyDBWithThreadMode,
+    SecondaryDBWithThreadMode, SingleThreaded, ThreadMode, DEFAULT_COLUMN_FAMILY_NAME,
+};
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Integer properties reported once per database.
+const DB_INT_PROPERTIES: &[Property] = &[
+    properties::BACKGROUND_ERRORS,
+    properties::NUM_SNAPSHOTS,
+    properties::OLDEST_SNAPSHOT_TIME,
+    properties::OLDEST_SNAPSHOT_SEQUENCE,
+    properties::IS_FILE_DELETIONS_ENABLED,
+    properties::MIN_LOG_NUMBER_TO_KEEP,
+    properties::MIN_OBSOLETE_SST_NUMBER_TO_KEEP,
+    properties::NUM_RUNNING_FLUSHES,
+    properties::NUM_RUNNING_COMPACTIONS,
+    properties::ACTUAL_DELAYED_WRITE_RATE,
+    properties::IS_WRITE_STOPPED,
+];
+
+/// Integer properties reported for every column family.
+const CF_INT_PROPERTIES: &[Property] = &[
+    properties::NUM_IMMUTABLE_MEM_TABLE,
+    properties::NUM_IMMUTABLE_MEM_TABLE_FLUSHED,
+    properties::MEM_TABLE_FLUSH_PENDING,
+    properties::COMPACTION_PENDING,
+    properties::CUR_SIZE_ACTIVE_MEM_TABLE,
+    properties::CUR_SIZE_ALL_MEM_TABLES,
+    properties::SIZE_ALL_MEM_TABLES,
+    properties::NUM_ENTRIES_ACTIVE_MEM_TABLE,
+    properties::NUM_ENTRIES_IMM_MEM_TABLES,
+    properties::NUM_DELETES_ACTIVE_MEM_TABLE,
+    properties::NUM_DELETES_IMM_MEM_TABLES,
+    properties::ESTIMATE_NUM_KEYS,
+    properties::ESTIMATE_TABLE_READERS_MEM,
+    properties::NUM_LIVE_VERSIONS,
+    properties::CURRENT_SUPER_VERSION_NUMBER,
+    properties::ESTIMATE_LIVE_DATA_SIZE,
+    properties::BASE_LEVEL,
+    properties::TOTAL_SST_FILES_SIZE,
+    properties::LIVE_SST_FILES_SIZE,
+    properties::ESTIMATE_PENDING_COMPACTION_BYTES,
+    properties::ESTIMATE_OLDEST_KEY_TIME,
+    properties::BLOCK_CACHE_CAPACITY,
+    properties::BLOCK_CACHE_USAGE,
+    properties::BLOCK_CACHE_PINNED_USAGE,
+];
+
+/// Quantiles of the histograms, in the order of the statistics string.
+const QUANTILES: &[(&str, &str)] = &[
+    ("P50", "0.5"),
+    ("P95", "0.95"),
+    ("P99", "0.99"),
+    ("P100", "1"),
+];
+
+mod private {
+    use crate::{perf::MemoryUsageStats, Error};
+    use std::path::Path;
+
+    pub trait Sealed {
+        fn source(&self) -> &dyn Source;
+    }
+
+    /// The properties read from a database, by the names of its column
+    /// families.
+    pub trait Source {
+        fn path(&self) -> &Path;
+
+        fn cf_names(&self) -> Vec<String>;
+
+        fn property_int_value(
+            &self,
+            cf_name: Option<&str>,
+            name: &str,
+        ) -> Result<Option<u64>, Error>;
+
+        fn property_value(
+            &self,
+            cf_name: Option<&str>,
+            name: &str,
+        ) -> Result<Option<String>, Error>;
+
+        fn memory_usage(&self) -> Result<MemoryUsageStats, Error>;
+    }
+}
+
+use private::Source;
+
+/// A database handle whose metrics can be rendered: [`DBWithThreadMode`],
+/// [`ReadOnlyDBWithThreadMode`] or [`SecondaryDBWithThreadMode`], in either
+/// thread mode.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait MetricsDB: private::Sealed {}
+
+macro_rules! impl_source {
+    ($($mode:ident),*) => {
+        $(
+            impl Source for DBWithThreadMode<$mode> {
+                fn path(&self) -> &Path {
+                    self.path()
+                }
+
+                fn cf_names(&self) -> Vec<String> {
+                    self.cf_names()
+                }
+
+                fn property_int_value(
+                    &self,
+                    cf_name: Option<&str>,
+                    name: &str,
+                ) -> Result<Option<u64>, Error> {
+                    // The default column family has no handle when the
+                    // database is opened without column families.
+                    match cf_name.and_then(|cf_name| self.cf_handle(cf_name)) {
+                        Some(cf) => self.property_int_value_cf(&cf, name),
+                        None => self.property_int_value(name),
+                    }
+                }
+
+                fn property_value(
+                    &self,
+                    cf_name: Option<&str>,
+                    name: &str,
+                ) -> Result<Option<String>, Error> {
+                    match cf_name.and_then(|cf_name| self.cf_handle(cf_name)) {
+                        Some(cf) => self.property_value_cf(&cf, name),
+                        None => self.property_value(name),
+                    }
+                }
+
+                fn memory_usage(&self) -> Result<crate::perf::MemoryUsageStats, Error> {
+                    get_db_memory_usage_stats(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_source!(SingleThreaded, MultiThreaded);
+
+impl<T: ThreadMode> private::Sealed for DBWithThreadMode<T>
+where
+    Self: Source,
+{
+    fn source(&self) -> &dyn Source {
+        self
+    }
+}
+
+impl<T: ThreadMode> MetricsDB for DBWithThreadMode<T> where Self: Source {}
+
+impl<T: ThreadMode> private::Sealed for ReadOnlyDBWithThreadMode<T>
+where
+    DBWithThreadMode<T>: Source,
+{
+    fn source(&self) -> &dyn Source {
+        self.inner_db()
+    }
+}
+
+impl<T: ThreadMode> MetricsDB for ReadOnlyDBWithThreadMode<T> where DBWithThreadMode<T>: Source {}
+
+impl<T: ThreadMode> private::Sealed for SecondaryDBWithThreadMode<T>
+where
+    DBWithThreadMode<T>: Source,
+{
+    fn source(&self) -> &dyn Source {
+        self.inner_db()
+    }
+}
+
+impl<T: ThreadMode> MetricsDB for SecondaryDBWithThreadMode<T> where DBWithThreadMode<T>: Source {}
+
+/// Collects the metrics of databases and caches, and renders them in the
+/// Prometheus text format.
+///
+/// Every sample is labelled with the labels added with
+/// [`add_label`](Self::add_label), the path of its database as `db`, and
+/// with the name of its column family as `cf` or of its cache as `cache`.
+pub struct MetricsRenderer<'a> {
+    prefix: String,
+    labels: Vec<(String, String)>,
+    dbs: Vec<(&'a dyn Source, Option<&'a Options>)>,
+    caches: Vec<(String, &'a Cache)>,
+}
+
+impl<'a> MetricsRenderer<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the prefix of all metric names.
+    ///
+    /// Default: `rocksdb`
+    pub fn set_prefix(&mut self, prefix: &str) {
+        self.prefix = sanitize_name(prefix);
+    }
+
+    /// Adds a label to all samples.
+    pub fn add_label(&mut self, name: &str, value: &str) {
+        self.labels.push((sanitize_name(name), value.to_owned()));
+    }
+
+    /// Adds the integer properties of the database and of its column families,
+    /// and its memory usage. Read-only and secondary handles are accepted too.
+    pub fn add_db<D: MetricsDB>(&mut self, db: &'a D) {
+        self.dbs.push((db.source(), None));
+    }
+
+    /// Adds the database like [`add_db`](Self::add_db), together with the
+    /// tickers and histograms of the statistics of `opts`, which must be the
+    /// options the database was opened with, with statistics enabled.
+    pub fn add_db_with_statistics<D: MetricsDB>(&mut self, db: &'a D, opts: &'a Options) {
+        self.dbs.push((db.source(), Some(opts)));
+    }
+
+    /// Adds the capacity and usage of a cache.
+    pub fn add_cache(&mut self, name: &str, cache: &'a Cache) {
+        self.caches.push((name.to_owned(), cache));
+    }
+
+    /// Renders the current value of all metrics.
+    pub fn render(&self) -> Result<String, Error> {
+        let mut families = Families::default();
+
+        for (db, opts) in &self.dbs {
+            let path = db.path().to_string_lossy().into_owned();
+            let db_labels = self.labels(&[("db", &path)]);
+
+            for property in DB_INT_PROPERTIES {
+                if let Some(value) = db.property_int_value(None, property.name())? {
+                    families.gauge(
+                        self.property_name(property.name()),
+                        property.name(),
+                        &db_labels,
+                        value,
+                    );
+                }
+            }
+
+            let mut cf_names = db.cf_names();
+            if !cf_names
+                .iter()
+                .any(|name| name == DEFAULT_COLUMN_FAMILY_NAME)
+            {
+                cf_names.insert(0, DEFAULT_COLUMN_FAMILY_NAME.to_owned());
+            }
+            for cf_name in &cf_names {
+                let cf_labels = self.labels(&[("db", &path), ("cf", cf_name)]);
+                for property in CF_INT_PROPERTIES {
+                    if let Some(value) = db.property_int_value(Some(cf_name), property.name())? {
+                        families.gauge(
+                            self.property_name(property.name()),
+                            property.name(),
+                            &cf_labels,
+                            value,
+                        );
+                    }
+                }
+
+                let name = self.property_name(NUM_FILES_AT_LEVEL_PREFIX);
+                let mut level = 0;
+                while let Some(files) = cf_files_at_level(*db, cf_name, level)? {
+                    let level_labels = self.labels(&[
+                        ("db", &path),
+                        ("cf", cf_name),
+                        ("level", &level.to_string()),
+                    ]);
+                    families.gauge(
+                        name.clone(),
+                        NUM_FILES_AT_LEVEL_PREFIX,
+                        &level_labels,
+                        files,
+                    );
+                    level += 1;
+                }
+            }
+
+            let usage = db.memory_usage()?;
+            for (name, value) in &[
+                ("mem_table_total_bytes", usage.mem_table_total),
+                ("mem_table_unflushed_bytes", usage.mem_table_unflushed),
+                ("table_readers_total_bytes", usage.mem_table_readers_total),
+                ("cache_total_bytes", usage.cache_total),
+            ] {
+                families.gauge(
+                    self.name(&format!("memory_{}", name)),
+                    "Approximate memory usage",
+                    &db_labels,
+                    *value,
+                );
+            }
+
+            if let Some(statistics) = opts.and_then(Options::get_statistics) {
+                self.add_statistics(&mut families, &statistics, &db_labels);
+            }
+        }
+
+        for (name, cache) in &self.caches {
+            let labels = self.labels(&[("cache", name)]);
+            for (metric, help, value) in &[
+                (
+                    "cache_capacity_bytes",
+                    "Capacity of the cache",
+                    cache.get_capacity(),
+                ),
+                (
+                    "cache_usage_bytes",
+                    "Memory used by the cache",
+                    cache.get_usage(),
+                ),
+                (
+                    "cache_pinned_usage_bytes",
+                    "Memory used by the pinned entries of the cache",
+                    cache.get_pinned_usage(),
+                ),
+            ] {
+                families.gauge(self.name(metric), help, &labels, *value as u64);
+            }
+        }
+
+        Ok(families.render())
+    }
+
+    /// Parses the tickers and histograms of a statistics string.
+    fn add_statistics(&self, families: &mut Families, statistics: &str, labels: &str) {
+        for line in statistics.lines() {
+            let mut fields = line.split_whitespace();
+            let stat = match fields.next() {
+                Some(stat) => stat,
+                None => continue,
+            };
+            // The remaining fields are `NAME : VALUE` triples.
+            let mut values = HashMap::new();
+            while let (Some(field), Some(":"), Some(value)) =
+                (fields.next(), fields.next(), fields.next())
+            {
+                values.insert(field, value);
+            }
+
+            let name = self.property_name(stat);
+            if values.len() == 1 {
+                if let Some(count) = values.get("COUNT").and_then(|count| count.parse().ok()) {
+                    families.counter(format!("{}_total", name), stat, labels, count);
+                }
+            } else {
+                let count = values
+                    .get("COUNT")
+                    .and_then(|count| count.parse::<u64>().ok());
+                let sum = values.get("SUM").and_then(|sum| sum.parse::<u64>().ok());
+                let (count, sum) = match (count, sum) {
+                    (Some(count), Some(sum)) => (count, sum),
+                    _ => continue,
+                };
+                let quantiles = QUANTILES
+                    .iter()
+                    .filter_map(|(field, quantile)| {
+                        let value = values.get(field)?.parse::<f64>().ok()?;
+                        Some((*quantile, value))
+                    })
+                    .collect();
+                families.summary(name, stat, labels, quantiles, count, sum);
+            }
+        }
+    }
+
+    /// Returns the metric name of a property or a statistic.
+    fn property_name(&self, property: &str) -> String {
+        self.name(property.strip_prefix("rocksdb.").unwrap_or(property))
+    }
+
+    fn name(&self, name: &str) -> String {
+        if self.prefix.is_empty() {
+            sanitize_name(name)
+        } else {
+            format!("{}_{}", self.prefix, sanitize_name(name))
+        }
+    }
+
+    /// Formats the common labels followed by `extra`, without the braces.
+    fn labels(&self, extra: &[(&str, &str)]) -> String {
+        let mut out = String::new();
+        let labels = self
+            .labels
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .chain(extra.iter().copied());
+        for (i, (name, value)) in labels.enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, "{}=\"{}\"", name, escape_label_value(value));
+        }
+        out
+    }
+}
+
+impl<'a> Default for MetricsRenderer<'a> {
+    fn default() -> Self {
+        Self {
+            prefix: "rocksdb".to_owned(),
+            labels: Vec::new(),
+            dbs: Vec::new(),
+            caches: Vec::new(),
+        }
+    }
+}
+
+fn cf_files_at_level(db: &dyn Source, cf_name: &str, level: usize) -> Result<Option<u64>, Error> {
+    let property = properties::num_files_at_level(level);
+    let value = db.property_value(Some(cf_name), property.name())?;
+    Ok(value.and_then(|value| value.trim().parse().ok()))
+}
+
+/// Replaces the characters which are not allowed in metric and label names.
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .enumerate()
+        .map(|(i, c)| match c {
+            'a'..='z' | 'A'..='Z' | '_' | ':' => c,
+            '0'..='9' if i > 0 => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Metric families in the order they were first added, with their samples.
+#[derive(Default)]
+struct Families {
+    families: Vec<Family>,
+    index: HashMap<String, usize>,
+}
+
+struct Family {
+    name: String,
+    help: String,
+    kind: &'static str,
+    samples: Vec<String>,
+}
+
+impl Families {
+    fn gauge(&mut self, name: String, help: &str, labels: &str, value: u64) {
+        let sample = format!("{}{{{}}} {}", name, labels, value);
+        self.family(name, help, "gauge").push(sample);
+    }
+
+    fn counter(&mut self, name: String, help: &str, labels: &str, value: u64) {
+        let sample = format!("{}{{{}}} {}", name, labels, value);
+        self.family(name, help, "counter").push(sample);
+    }
+
+    fn summary(
+        &mut self,
+        name: String,
+        help: &str,
+        labels: &str,
+        quantiles: Vec<(&str, f64)>,
+        count: u64,
+        sum: u64,
+    ) {
+        let separator = if labels.is_empty() { "" } else { "," };
+        let mut samples: Vec<String> = quantiles
+            .into_iter()
+            .map(|(quantile, value)| {
+                format!(
+                    "{}{{{}{}quantile=\"{}\"}} {}",
+                    name, labels, separator, quantile, value
+                )
+            })
+            .collect();
+        samples.push(format!("{}_sum{{{}}} {}", name, labels, sum));
+        samples.push(format!("{}_count{{{}}} {}", name, labels, count));
+        self.family(name, help, "summary").extend(samples);
+    }
+
+    fn family(&mut self, name: String, help: &str, kind: &'static str) -> &mut Vec<String> {
+        let families = &mut self.families;
+        let index = *self.index.entry(name.clone()).or_insert_with(|| {
+            families.push(Family {
+                name,
+                help: help.to_owned(),
+                kind,
+                samples: Vec::new(),
+            });
+            families.len() - 1
+        });
+        &mut self.families[index].samples
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::new();
+        for family in &self.families {
+            let _ = writeln!(out, "# HELP {} {}", family.name, family.help);
+            let _ = writeln!(out, "# TYPE {} {}", family.name, family.kind);
+            for sample in &family.samples {
+                out.push_str(sample);
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
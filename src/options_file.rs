@@ -0,0 +1,1576 @@
+// Copyright 2021 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reading and writing of the OPTIONS files RocksDB keeps next to its data.
+//!
+//! The C API doesn't expose RocksDB's OPTIONS file parser, so the INI-like
+//! format (see `examples/rocksdb_option_file_example.ini` in RocksDB) is handled
+//! here and every option is mapped onto the corresponding setter. Options
+//! without a setter are applied through RocksDB's options string parser when
+//! loading, and omitted when persisting unless they can be read back.
+
+use crate::{
+    ffi, BlockBasedIndexType, BlockBasedOptions, Cache, ColumnFamilyDescriptor, CuckooTableOptions,
+    DBCompressionType, DataBlockIndexType, Env, Error, Options, PlainTableFactoryOptions,
+    SliceTransform, DEFAULT_COLUMN_FAMILY_NAME,
+};
+
+use libc::{c_int, c_uchar};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Version of the bundled RocksDB, written to the `[Version]` section.
+const ROCKSDB_VERSION: &str = "6.20.3";
+const OPTIONS_FILE_VERSION: &str = "1.1";
+const NULLPTR: &str = "nullptr";
+const BYTEWISE_COMPARATOR: &str = "leveldb.BytewiseComparator";
+const BLOCK_BASED_TABLE: &str = "BlockBasedTable";
+/// Value of `ttl` and `periodic_compaction_seconds` letting RocksDB pick the
+/// default when the database is opened.
+const DEFAULT_SECONDS: u64 = 0xffff_ffff_ffff_fffe;
+/// Column family options given by name rather than by value.
+const NAMED_OPTIONS: &[&str] = &[
+    "comparator",
+    "merge_operator",
+    "compaction_filter",
+    "compaction_filter_factory",
+    "prefix_extractor",
+    "table_factory",
+];
+
+type Callback = Box<dyn Fn(&mut Options)>;
+type FilterPolicyCallback = Box<dyn Fn(&mut BlockBasedOptions)>;
+
+/// Loads OPTIONS files, re-attaching Rust callbacks by the name RocksDB
+/// recorded for them.
+///
+/// Merge operators, comparators, compaction filters, compaction filter
+/// factories and prefix extractors can't be restored from their name alone.
+/// Register a closure for each of them that installs the callback on the
+/// `Options` being loaded. Built-in comparators and prefix extractors, as well
+/// as unset callbacks (`nullptr`), need no registration.
+///
+/// Bloom filters written by [`Options::persist_to_file`] keep their bits per
+/// key. RocksDB itself only records the name of the filter policy though, so
+/// a `rocksdb.BuiltinBloomFilter` in an OPTIONS file written by RocksDB is
+/// loaded as a full bloom filter with 10 bits per key unless a filter policy
+/// is registered under that name with
+/// [`register_filter_policy`](Self::register_filter_policy).
+///
+/// # Examples
+///
+/// ```
+/// use rocksdb::{Cache, Env, MergeOperands, Options, OptionsLoader, DB};
+///
+/// fn concat_merge(_: &[u8], existing: Option<&[u8]>, operands: &mut MergeOperands) -> Option<Vec<u8>> {
+///     let mut result = existing.map(<[u8]>::to_vec).unwrap_or_default();
+///     for op in operands {
+///         result.extend_from_slice(op);
+///     }
+///     Some(result)
+/// }
+///
+/// let path = "_path_for_rocksdb_storage_options_loader";
+/// {
+///     let mut opts = Options::default();
+///     opts.create_if_missing(true);
+///     opts.set_merge_operator_associative("concat", concat_merge);
+///     let db = DB::open(&opts, path).unwrap();
+///     db.merge(b"k", b"a").unwrap();
+/// }
+/// {
+///     let mut loader = OptionsLoader::default();
+///     loader.register_merge_operator("concat", |opts| {
+///         opts.set_merge_operator_associative("concat", concat_merge)
+///     });
+///     let env = Env::default().unwrap();
+///     let cache = Cache::new_lru_cache(8 << 20).unwrap();
+///     let (opts, cfs) = loader.load_latest(path, &env, &cache).unwrap();
+///     let db = DB::open_cf_descriptors(&opts, path, cfs).unwrap();
+///     db.merge(b"k", b"b").unwrap();
+///     assert_eq!(db.get(b"k").unwrap().unwrap(), b"ab");
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+#[derive(Default)]
+pub struct OptionsLoader {
+    callbacks: HashMap<(&'static str, String), Callback>,
+    filter_policies: HashMap<String, FilterPolicyCallback>,
+}
+
+impl OptionsLoader {
+    /// Registers how to attach the merge operator recorded as `name`.
+    pub fn register_merge_operator<F>(&mut self, name: &str, attach: F)
+    where
+        F: Fn(&mut Options) + 'static,
+    {
+        self.register("merge_operator", name, attach);
+    }
+
+    /// Registers how to attach the comparator recorded as `name`.
+    pub fn register_comparator<F>(&mut self, name: &str, attach: F)
+    where
+        F: Fn(&mut Options) + 'static,
+    {
+        self.register("comparator", name, attach);
+    }
+
+    /// Registers how to attach the compaction filter recorded as `name`.
+    pub fn register_compaction_filter<F>(&mut self, name: &str, attach: F)
+    where
+        F: Fn(&mut Options) + 'static,
+    {
+        self.register("compaction_filter", name, attach);
+    }
+
+    /// Registers how to attach the compaction filter factory recorded as `name`.
+    pub fn register_compaction_filter_factory<F>(&mut self, name: &str, attach: F)
+    where
+        F: Fn(&mut Options) + 'static,
+    {
+        self.register("compaction_filter_factory", name, attach);
+    }
+
+    /// Registers how to attach the prefix extractor recorded as `name`.
+    ///
+    /// Fixed-prefix and no-op extractors are restored without registration.
+    pub fn register_prefix_extractor<F>(&mut self, name: &str, attach: F)
+    where
+        F: Fn(&mut Options) + 'static,
+    {
+        self.register("prefix_extractor", name, attach);
+    }
+
+    /// Registers how to set the filter policy recorded as `name` on the
+    /// block-based table options being loaded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rocksdb::{FilterPolicy, OptionsLoader};
+    ///
+    /// let mut loader = OptionsLoader::default();
+    /// loader.register_filter_policy("rocksdb.BuiltinBloomFilter", |block_opts| {
+    ///     block_opts.set_filter_policy(FilterPolicy::Bloom { bits_per_key: 16 })
+    /// });
+    /// ```
+    pub fn register_filter_policy<F>(&mut self, name: &str, attach: F)
+    where
+        F: Fn(&mut BlockBasedOptions) + 'static,
+    {
+        self.filter_policies
+            .insert(name.to_owned(), Box::new(attach));
+    }
+
+    fn register<F>(&mut self, kind: &'static str, name: &str, attach: F)
+    where
+        F: Fn(&mut Options) + 'static,
+    {
+        self.callbacks
+            .insert((kind, name.to_owned()), Box::new(attach));
+    }
+
+    /// Loads the most recent OPTIONS file of the database at `path`.
+    ///
+    /// Returns the DB options, which include the options of the default
+    /// column family, together with a descriptor for every column family.
+    /// `env` is set on the DB options and `cache` is used as block cache by
+    /// every column family with a block-based table.
+    pub fn load_latest<P: AsRef<Path>>(
+        &self,
+        path: P,
+        env: &Env,
+        cache: &Cache,
+    ) -> Result<(Options, Vec<ColumnFamilyDescriptor>), Error> {
+        let file = latest_options_file(path.as_ref())?;
+        self.load_from_file(file, env, cache)
+    }
+
+    /// Loads the OPTIONS file at `file`, see [`load_latest`](Self::load_latest).
+    pub fn load_from_file<P: AsRef<Path>>(
+        &self,
+        file: P,
+        env: &Env,
+        cache: &Cache,
+    ) -> Result<(Options, Vec<ColumnFamilyDescriptor>), Error> {
+        let file = OptionsFile::read(file.as_ref())?;
+
+        let mut db_opts = Options::default();
+        set_options(&mut db_opts, DB_OPTIONS, &file.db_options)?;
+        set_unmapped_options(&mut db_opts, &file.db_options)?;
+        db_opts.set_env(env);
+
+        let mut cfs = Vec::with_capacity(file.column_families.len());
+        for section in &file.column_families {
+            if section.name == DEFAULT_COLUMN_FAMILY_NAME {
+                self.apply_column_family(&mut db_opts, section, Some(cache))?;
+            }
+            let mut cf_opts = Options::default();
+            self.apply_column_family(&mut cf_opts, section, Some(cache))?;
+            cfs.push(ColumnFamilyDescriptor::new(section.name.as_str(), cf_opts));
+        }
+        Ok((db_opts, cfs))
+    }
+
+    /// Creates options from a string of `name=value` pairs separated by `;`,
+    /// as produced by [`Options::to_options_string`].
+    ///
+    /// Unlike OPTIONS files, unknown option names are rejected. Table options
+    /// nested under `block_based_table_factory` or `plain_table_factory` are
+    /// applied to a new table factory; no block cache is set on it.
+    pub fn options_from_string(&self, options: &str) -> Result<Options, Error> {
+        let mut values = split_options_string(options)?;
+        let mut table = None;
+        if let Some(pos) = values
+            .iter()
+            .position(|(key, _)| key == "block_based_table_factory" || key == "plain_table_factory")
+        {
+            let (key, value) = values.remove(pos);
+            let table_options = split_options_string(&value)?;
+            let known = |name: &str| match key.as_str() {
+                "plain_table_factory" => PLAIN_TABLE_OPTIONS.iter().any(|e| e.name == name),
+                _ => BLOCK_BASED_TABLE_OPTIONS.iter().any(|e| e.name == name),
+            };
+            if let Some((name, _)) = table_options.iter().find(|(name, _)| !known(name)) {
+                return Err(Error::new(format!("Unknown option {}.{}", key, name)));
+            }
+            table = Some(TableSection {
+                factory: match key.as_str() {
+                    "plain_table_factory" => "PlainTable",
+                    _ => BLOCK_BASED_TABLE,
+                }
+                .to_owned(),
+                options: table_options,
+            });
+        }
+        if let Some((key, _)) = values.iter().find(|(key, _)| {
+            !NAMED_OPTIONS.contains(&key.as_str())
+                && !DB_OPTIONS
+                    .iter()
+                    .chain(CF_OPTIONS)
+                    .any(|entry| entry.name == key)
+        }) {
+            return Err(Error::new(format!("Unknown option {}", key)));
+        }
+
+        let mut opts = Options::default();
+        set_options(&mut opts, DB_OPTIONS, &values)?;
+        let section = ColumnFamilySection {
+            name: DEFAULT_COLUMN_FAMILY_NAME.to_owned(),
+            options: values,
+            table,
+        };
+        self.apply_column_family(&mut opts, &section, None)?;
+        Ok(opts)
+    }
+
+    fn apply_column_family(
+        &self,
+        opts: &mut Options,
+        section: &ColumnFamilySection,
+        cache: Option<&Cache>,
+    ) -> Result<(), Error> {
+        set_options(opts, CF_OPTIONS, &section.options)?;
+        set_unmapped_options(opts, &section.options)?;
+
+        let mut table_factory = BLOCK_BASED_TABLE;
+        for (key, value) in &section.options {
+            match key.as_str() {
+                "table_factory" => table_factory = value,
+                "comparator"
+                | "merge_operator"
+                | "compaction_filter"
+                | "compaction_filter_factory"
+                | "prefix_extractor" => self.attach(opts, key, value)?,
+                _ => {}
+            }
+        }
+
+        let table_options = section
+            .table
+            .as_ref()
+            .map_or(&[][..], |table| &table.options[..]);
+        match table_factory {
+            BLOCK_BASED_TABLE => {
+                let mut factory = BlockBasedOptions::default();
+                set_options(&mut factory, BLOCK_BASED_TABLE_OPTIONS, table_options)?;
+                if let Some(attach) = table_options
+                    .iter()
+                    .find(|(key, _)| key == "filter_policy")
+                    .and_then(|(_, name)| self.filter_policies.get(name))
+                {
+                    attach(&mut factory);
+                }
+                let no_block_cache = table_options
+                    .iter()
+                    .any(|(key, value)| key == "no_block_cache" && parse_bool(value) == Some(true));
+                if let (false, Some(cache)) = (no_block_cache, cache) {
+                    factory.set_block_cache(cache);
+                }
+                opts.set_block_based_table_factory(&factory);
+            }
+            "PlainTable" => {
+                let mut factory = PlainTableFactoryOptions {
+                    user_key_length: 0,
+                    bloom_bits_per_key: 10,
+                    hash_table_ratio: 0.75,
+                    index_sparseness: 16,
+                };
+                set_options(&mut factory, PLAIN_TABLE_OPTIONS, table_options)?;
+                opts.set_plain_table_factory(&factory);
+            }
+            "CuckooTable" => {
+                let mut factory = CuckooTableOptions::default();
+                set_options(&mut factory, CUCKOO_TABLE_OPTIONS, table_options)?;
+                opts.set_cuckoo_table_factory(&factory);
+            }
+            other => {
+                return Err(Error::new(format!(
+                    "Unsupported table factory {} for column family \"{}\"",
+                    other, section.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn attach(&self, opts: &mut Options, kind: &str, name: &str) -> Result<(), Error> {
+        if name == NULLPTR || (kind == "comparator" && name == BYTEWISE_COMPARATOR) {
+            return Ok(());
+        }
+        if kind == "prefix_extractor" {
+            if name == "rocksdb.Noop" {
+                opts.set_prefix_extractor(SliceTransform::create_noop());
+                return Ok(());
+            }
+            if let Some(len) = name
+                .strip_prefix("rocksdb.FixedPrefix.")
+                .and_then(|len| len.parse().ok())
+            {
+                opts.set_prefix_extractor(SliceTransform::create_fixed_prefix(len));
+                return Ok(());
+            }
+        }
+        match self
+            .callbacks
+            .iter()
+            .find(|((k, n), _)| *k == kind && n == name)
+        {
+            Some((_, attach)) => {
+                attach(opts);
+                Ok(())
+            }
+            None => Err(Error::new(format!(
+                "No {} registered under the name \"{}\"",
+                kind, name
+            ))),
+        }
+    }
+}
+
+impl Options {
+    /// Loads the most recent OPTIONS file of the database at `path`.
+    ///
+    /// This is a shortcut for [`OptionsLoader::load_latest`] without any
+    /// registered callbacks, so it fails if the database uses a merge operator,
+    /// a custom comparator or a compaction filter.
+    pub fn load_latest<P: AsRef<Path>>(
+        path: P,
+        env: &Env,
+        cache: &Cache,
+    ) -> Result<(Options, Vec<ColumnFamilyDescriptor>), Error> {
+        OptionsLoader::default().load_latest(path, env, cache)
+    }
+
+    /// Loads the OPTIONS file at `file`, see [`Options::load_latest`].
+    pub fn load_from_file<P: AsRef<Path>>(
+        file: P,
+        env: &Env,
+        cache: &Cache,
+    ) -> Result<(Options, Vec<ColumnFamilyDescriptor>), Error> {
+        OptionsLoader::default().load_from_file(file, env, cache)
+    }
+
+    /// Writes these options and the given column families to `file` in the
+    /// OPTIONS file format.
+    ///
+    /// These options are written as the default column family unless `cfs`
+    /// contains a descriptor for it. The options of block-based and plain
+    /// table factories are written to `[TableOptions/...]` sections, except
+    /// for block caches, which are passed to the loader instead. Other options
+    /// that can't be read back through the C API are omitted and take their
+    /// default value when loading the file.
+    pub fn persist_to_file<P: AsRef<Path>>(
+        &self,
+        file: P,
+        cfs: &[ColumnFamilyDescriptor],
+    ) -> Result<(), Error> {
+        let mut column_families = Vec::with_capacity(cfs.len() + 1);
+        if !cfs.iter().any(|cf| cf.name == DEFAULT_COLUMN_FAMILY_NAME) {
+            column_families.push(ColumnFamilySection::describe(
+                DEFAULT_COLUMN_FAMILY_NAME,
+                self,
+            ));
+        }
+        column_families.extend(
+            cfs.iter()
+                .map(|cf| ColumnFamilySection::describe(&cf.name, &cf.options)),
+        );
+
+        let file_contents = OptionsFile {
+            db_options: get_options(self, DB_OPTIONS),
+            column_families,
+        };
+        let file = file.as_ref();
+        fs::write(file, file_contents.to_string()).map_err(|e| {
+            Error::new(format!(
+                "Failed to write options file {}: {}",
+                file.display(),
+                e
+            ))
+        })
+    }
+
+    /// Checks that these options and column family descriptors can be used to
+    /// open the existing database at `path`.
+    ///
+    /// Like RocksDB's loose compatibility check, this verifies that every
+    /// column family of the database is opened and that comparators, merge
+    /// operators and table factories match the ones in its latest OPTIONS file.
+    /// A merge operator may be added to or removed from a column family.
+    pub fn check_compatibility<P: AsRef<Path>>(
+        &self,
+        path: P,
+        cfs: &[ColumnFamilyDescriptor],
+    ) -> Result<(), Error> {
+        let file = OptionsFile::read(&latest_options_file(path.as_ref())?)?;
+
+        let not_opened: Vec<&str> = file
+            .column_families
+            .iter()
+            .map(|section| section.name.as_str())
+            .filter(|name| {
+                *name != DEFAULT_COLUMN_FAMILY_NAME && !cfs.iter().any(|cf| cf.name == *name)
+            })
+            .collect();
+        if !not_opened.is_empty() {
+            return Err(Error::new(format!(
+                "Column families not opened: {}",
+                not_opened.join(", ")
+            )));
+        }
+
+        for section in &file.column_families {
+            let opts = cfs
+                .iter()
+                .find(|cf| cf.name == section.name)
+                .map_or(self, |cf| &cf.options);
+            let specified_section = ColumnFamilySection::describe(&section.name, opts);
+            for key in &["comparator", "merge_operator", "table_factory"] {
+                let specified = specified_section.get(key).unwrap_or(NULLPTR);
+                let existing = section.get(key).unwrap_or(match *key {
+                    "comparator" => BYTEWISE_COMPARATOR,
+                    "table_factory" => BLOCK_BASED_TABLE,
+                    _ => NULLPTR,
+                });
+                let loose =
+                    *key == "merge_operator" && (specified == NULLPTR || existing == NULLPTR);
+                if specified != existing && !loose {
+                    return Err(Error::new(format!(
+                        "Incompatible {} for column family \"{}\": the specified one is {} \
+                         while the persisted one is {}",
+                        key, section.name, specified, existing
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An option whose value differs between two [`Options`], see [`Options::diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionDiff {
+    /// Name of the option as used in OPTIONS files
+    pub name: String,
+    /// Value in the options `diff` was called on
+    pub left: String,
+    /// Value in the options passed to `diff`
+    pub right: String,
+}
+
+impl Options {
+    /// Formats the options readable through the C API as `name=value` pairs
+    /// separated by `;`, using the names and value formats of OPTIONS files.
+    ///
+    /// Callbacks such as merge operators are included by name, which
+    /// [`OptionsLoader::options_from_string`] uses to re-attach them. The
+    /// options of block-based and plain table factories are nested under
+    /// `block_based_table_factory` and `plain_table_factory`, as far as they
+    /// were set through this crate; block caches are never included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_max_open_files(100);
+    /// let restored = Options::from_options_string(&opts.to_options_string()).unwrap();
+    /// assert_eq!(restored.get_max_open_files(), 100);
+    /// assert!(opts.diff(&restored).is_empty());
+    /// ```
+    pub fn to_options_string(&self) -> String {
+        let (mut options, table) = self.describe();
+        if let Some(table) = table {
+            options.push((
+                table_factory_option(&table.factory).to_owned(),
+                join_options(&table.options),
+            ));
+        }
+        join_options(&options)
+    }
+
+    /// Creates options from a string produced by
+    /// [`to_options_string`](Self::to_options_string).
+    ///
+    /// This is a shortcut for [`OptionsLoader::options_from_string`] without
+    /// any registered callbacks. A block-based table factory is created with
+    /// the nested table options, but without a block cache, so RocksDB's
+    /// default cache is used unless `no_block_cache` is set.
+    pub fn from_options_string(options: &str) -> Result<Options, Error> {
+        OptionsLoader::default().options_from_string(options)
+    }
+
+    /// Lists the options whose values differ between `self` and `other`.
+    ///
+    /// Only options readable through the C API, the names of callbacks and
+    /// the table options set through this crate are compared. Table options
+    /// are named like `block_based_table_factory.block_size`; one set on only
+    /// one side is reported with an empty value on the other, even if it was
+    /// set to its default. Block caches are not compared.
+    pub fn diff(&self, other: &Options) -> Vec<OptionDiff> {
+        let (left, left_table) = self.describe();
+        let (right, right_table) = other.describe();
+        let mut diff: Vec<OptionDiff> = left
+            .into_iter()
+            .zip(right)
+            .filter(|((_, left), (_, right))| left != right)
+            .map(|((name, left), (_, right))| OptionDiff { name, left, right })
+            .collect();
+
+        let prefix = table_factory_option(
+            left_table
+                .as_ref()
+                .or(right_table.as_ref())
+                .map_or(BLOCK_BASED_TABLE, |table| table.factory.as_str()),
+        );
+        let left_table = left_table.map(|table| table.options).unwrap_or_default();
+        let right_table = right_table.map(|table| table.options).unwrap_or_default();
+        let find = |options: &[(String, String)], key: &str| {
+            options
+                .iter()
+                .find(|(k, _)| k == key)
+                .map_or_else(String::new, |(_, v)| v.clone())
+        };
+        let mut keys: Vec<&str> = left_table.iter().map(|(k, _)| k.as_str()).collect();
+        for (key, _) in &right_table {
+            if !keys.contains(&key.as_str()) {
+                keys.push(key);
+            }
+        }
+        for key in keys {
+            let left = find(&left_table, key);
+            let right = find(&right_table, key);
+            if left != right {
+                diff.push(OptionDiff {
+                    name: format!("{}.{}", prefix, key),
+                    left,
+                    right,
+                });
+            }
+        }
+        diff
+    }
+
+    fn describe(&self) -> (Vec<(String, String)>, Option<TableSection>) {
+        let section = ColumnFamilySection::describe(DEFAULT_COLUMN_FAMILY_NAME, self);
+        let mut options = get_options(self, DB_OPTIONS);
+        options.extend(section.options);
+        (options, section.table)
+    }
+}
+
+/// Returns the name under which the options of `factory` are nested in
+/// options strings.
+fn table_factory_option(factory: &str) -> &'static str {
+    match factory {
+        "PlainTable" => "plain_table_factory",
+        "CuckooTable" => "cuckoo_table_factory",
+        _ => "block_based_table_factory",
+    }
+}
+
+/// Joins `name=value` pairs with `;`, putting braces around values which
+/// contain separators.
+fn join_options(options: &[(String, String)]) -> String {
+    options
+        .iter()
+        .map(|(key, value)| {
+            if value.contains(&[';', '{', '}'][..]) {
+                format!("{}={{{}}};", key, value)
+            } else {
+                format!("{}={};", key, value)
+            }
+        })
+        .collect()
+}
+
+/// Splits `name=value` pairs at the `;` outside of braces, stripping the
+/// braces around values.
+fn split_options_string(options: &str) -> Result<Vec<(String, String)>, Error> {
+    let mut pairs = Vec::new();
+    let mut depth = 0_usize;
+    let mut start = 0;
+    for (pos, c) in options.char_indices().chain(Some((options.len(), ';'))) {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| Error::new("Unbalanced '}' in options string".to_owned()))?;
+            }
+            ';' if depth == 0 => {
+                let pair = options[start..pos].trim();
+                start = pos + 1;
+                if pair.is_empty() {
+                    continue;
+                }
+                let eq = pair
+                    .find('=')
+                    .ok_or_else(|| Error::new(format!("Missing '=' in option \"{}\"", pair)))?;
+                let key = pair[..eq].trim();
+                let mut value = pair[eq + 1..].trim();
+                if value.starts_with('{') && value.ends_with('}') {
+                    value = &value[1..value.len() - 1];
+                }
+                pairs.push((key.to_owned(), value.to_owned()));
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return Err(Error::new("Unbalanced '{' in options string".to_owned()));
+    }
+    Ok(pairs)
+}
+
+/// Returns the OPTIONS file with the highest number in the directory `path`.
+pub(crate) fn latest_options_file(path: &Path) -> Result<PathBuf, Error> {
+    let entries = fs::read_dir(path).map_err(|e| {
+        Error::new(format!(
+            "Failed to list the directory {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let number = entry
+                .file_name()
+                .to_str()?
+                .strip_prefix("OPTIONS-")?
+                .parse::<u64>()
+                .ok()?;
+            Some((number, entry.path()))
+        })
+        .max_by_key(|(number, _)| *number)
+        .map(|(_, file)| file)
+        .ok_or_else(|| Error::new(format!("No OPTIONS file found in {}", path.display())))
+}
+
+/// The sections of an OPTIONS file, with values unescaped.
+pub(crate) struct OptionsFile {
+    pub(crate) db_options: Vec<(String, String)>,
+    pub(crate) column_families: Vec<ColumnFamilySection>,
+}
+
+pub(crate) struct ColumnFamilySection {
+    pub(crate) name: String,
+    pub(crate) options: Vec<(String, String)>,
+    pub(crate) table: Option<TableSection>,
+}
+
+pub(crate) struct TableSection {
+    pub(crate) factory: String,
+    pub(crate) options: Vec<(String, String)>,
+}
+
+impl ColumnFamilySection {
+    /// Describes the column family options readable from `opts`, along with
+    /// the table options recorded when its table factory was set.
+    pub(crate) fn describe(name: &str, opts: &Options) -> Self {
+        let mut options = get_options(opts, CF_OPTIONS);
+        let names = &opts.names;
+        let named = [
+            (
+                "comparator",
+                names.comparator.as_deref(),
+                BYTEWISE_COMPARATOR,
+            ),
+            ("merge_operator", names.merge_operator.as_deref(), NULLPTR),
+            (
+                "compaction_filter",
+                names.compaction_filter.as_deref(),
+                NULLPTR,
+            ),
+            (
+                "compaction_filter_factory",
+                names.compaction_filter_factory.as_deref(),
+                NULLPTR,
+            ),
+            (
+                "prefix_extractor",
+                names.prefix_extractor.as_deref(),
+                NULLPTR,
+            ),
+            ("table_factory", names.table_factory, BLOCK_BASED_TABLE),
+        ];
+        for (key, value, default) in &named {
+            options.push(((*key).to_owned(), value.unwrap_or(default).to_owned()));
+        }
+        let table = match names.table_factory {
+            Some("CuckooTable") => None,
+            factory => Some(TableSection {
+                factory: factory.unwrap_or(BLOCK_BASED_TABLE).to_owned(),
+                options: names.table_options.clone(),
+            }),
+        };
+        Self {
+            name: name.to_owned(),
+            options,
+            table,
+        }
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+enum Section {
+    None,
+    Version,
+    DBOptions,
+    CFOptions,
+    TableOptions,
+}
+
+impl OptionsFile {
+    pub(crate) fn read(file: &Path) -> Result<Self, Error> {
+        let content = fs::read_to_string(file).map_err(|e| {
+            Error::new(format!(
+                "Failed to read options file {}: {}",
+                file.display(),
+                e
+            ))
+        })?;
+        Self::parse(&content).map_err(|e| {
+            Error::new(format!(
+                "Failed to parse options file {}: {}",
+                file.display(),
+                e
+            ))
+        })
+    }
+
+    pub(crate) fn parse(content: &str) -> Result<Self, Error> {
+        let mut db_options = Vec::new();
+        let mut column_families: Vec<ColumnFamilySection> = Vec::new();
+        let mut section = Section::None;
+
+        for (number, line) in content.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = |what: &str| Error::new(format!("{} at line {}", what, number + 1));
+
+            if line.starts_with('[') && line.ends_with(']') {
+                let header = &line[1..line.len() - 1];
+                let (kind, name) = match header.find(' ') {
+                    Some(pos) => {
+                        let name = header[pos + 1..].trim();
+                        if name.len() < 2 || !name.starts_with('"') || !name.ends_with('"') {
+                            return Err(invalid("Invalid section name"));
+                        }
+                        (&header[..pos], Some(unescape(&name[1..name.len() - 1])))
+                    }
+                    None => (header, None),
+                };
+                section =
+                    match (kind, name) {
+                        ("Version", None) => Section::Version,
+                        ("DBOptions", None) => Section::DBOptions,
+                        ("CFOptions", Some(name)) => {
+                            column_families.push(ColumnFamilySection {
+                                name,
+                                options: Vec::new(),
+                                table: None,
+                            });
+                            Section::CFOptions
+                        }
+                        (kind, Some(name)) if kind.starts_with("TableOptions/") => {
+                            match column_families.last_mut() {
+                                Some(cf) if cf.name == name && cf.table.is_none() => {
+                                    cf.table = Some(TableSection {
+                                        factory: kind["TableOptions/".len()..].to_owned(),
+                                        options: Vec::new(),
+                                    });
+                                }
+                                _ => return Err(invalid(
+                                    "Table options don't follow the options of their column family",
+                                )),
+                            }
+                            Section::TableOptions
+                        }
+                        _ => return Err(invalid("Unknown section")),
+                    };
+                continue;
+            }
+
+            let pos = line.find('=').ok_or_else(|| invalid("Missing '='"))?;
+            let key = line[..pos].trim().to_owned();
+            let value = unescape(line[pos + 1..].trim());
+            if key.is_empty() {
+                return Err(invalid("Empty option name"));
+            }
+            match section {
+                Section::None => return Err(invalid("Option outside of a section")),
+                Section::Version => {}
+                Section::DBOptions => db_options.push((key, value)),
+                Section::CFOptions => {
+                    if let Some(cf) = column_families.last_mut() {
+                        cf.options.push((key, value));
+                    }
+                }
+                Section::TableOptions => {
+                    if let Some(table) = column_families.last_mut().and_then(|cf| cf.table.as_mut())
+                    {
+                        table.options.push((key, value));
+                    }
+                }
+            }
+        }
+
+        if !column_families
+            .iter()
+            .any(|cf| cf.name == DEFAULT_COLUMN_FAMILY_NAME)
+        {
+            return Err(Error::new(
+                "Missing options of the default column family".to_owned(),
+            ));
+        }
+        Ok(Self {
+            db_options,
+            column_families,
+        })
+    }
+}
+
+impl fmt::Display for OptionsFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# This is a RocksDB option file.")?;
+        writeln!(f, "#")?;
+        writeln!(
+            f,
+            "# For detailed file format spec, please refer to the example file"
+        )?;
+        writeln!(f, "# in examples/rocksdb_option_file_example.ini")?;
+        writeln!(f, "#")?;
+        writeln!(f)?;
+        writeln!(f, "[Version]")?;
+        writeln!(f, "  rocksdb_version={}", ROCKSDB_VERSION)?;
+        writeln!(f, "  options_file_version={}", OPTIONS_FILE_VERSION)?;
+        writeln!(f)?;
+        writeln!(f, "[DBOptions]")?;
+        write_options(f, &self.db_options)?;
+        for cf in &self.column_families {
+            writeln!(f)?;
+            writeln!(f, "[CFOptions \"{}\"]", escape(&cf.name))?;
+            write_options(f, &cf.options)?;
+            if let Some(table) = &cf.table {
+                writeln!(f)?;
+                writeln!(
+                    f,
+                    "[TableOptions/{} \"{}\"]",
+                    table.factory,
+                    escape(&cf.name)
+                )?;
+                write_options(f, &table.options)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_options(f: &mut fmt::Formatter, options: &[(String, String)]) -> fmt::Result {
+    for (key, value) in options {
+        writeln!(f, "  {}={}", key, escape(value))?;
+    }
+    Ok(())
+}
+
+/// Cuts `line` at the first `#` that isn't escaped.
+fn strip_comment(line: &str) -> &str {
+    let mut escaped = false;
+    for (pos, c) in line.char_indices() {
+        match c {
+            '#' if !escaped => return &line[..pos],
+            '\\' => escaped = !escaped,
+            _ => escaped = false,
+        }
+    }
+    line
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | ':' | '#' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Maps an option name of the OPTIONS file onto the target `T`.
+pub(crate) struct OptionEntry<T> {
+    pub(crate) name: &'static str,
+    pub(crate) get: Option<fn(&T) -> String>,
+    pub(crate) set: fn(&mut T, &str) -> Option<()>,
+}
+
+pub(crate) fn set_options<T>(
+    target: &mut T,
+    entries: &[OptionEntry<T>],
+    values: &[(String, String)],
+) -> Result<(), Error> {
+    for (key, value) in values {
+        if let Some(entry) = entries.iter().find(|entry| entry.name == key) {
+            (entry.set)(target, value).ok_or_else(|| {
+                Error::new(format!("Invalid value \"{}\" for option {}", value, key))
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Applies the options RocksDB writes to OPTIONS files which have no entry in
+/// [`DB_OPTIONS`] or [`CF_OPTIONS`], such as `compression_opts`, through its
+/// own options string parser. Fails on names RocksDB doesn't know either.
+fn set_unmapped_options(opts: &mut Options, values: &[(String, String)]) -> Result<(), Error> {
+    let unmapped: Vec<String> = values
+        .iter()
+        .filter(|(key, _)| {
+            !NAMED_OPTIONS.contains(&key.as_str())
+                && !DB_OPTIONS
+                    .iter()
+                    .chain(CF_OPTIONS)
+                    .any(|entry| entry.name == key)
+        })
+        .map(|(key, value)| {
+            if value.contains(';') && !value.starts_with('{') {
+                format!("{}={{{}}}", key, value)
+            } else {
+                format!("{}={}", key, value)
+            }
+        })
+        .collect();
+    if unmapped.is_empty() {
+        return Ok(());
+    }
+    opts.set_from_string(&unmapped.join(";"))
+}
+
+pub(crate) fn get_options<T>(target: &T, entries: &[OptionEntry<T>]) -> Vec<(String, String)> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let get = entry.get?;
+            Some((entry.name.to_owned(), get(target)))
+        })
+        .collect()
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_num<T: std::str::FromStr>(value: &str) -> Option<T> {
+    value.parse().ok()
+}
+
+fn parse_list<T>(value: &str, parse: impl Fn(&str) -> Option<T>) -> Option<Vec<T>> {
+    if value.is_empty() {
+        return Some(Vec::new());
+    }
+    value.split(':').map(|item| parse(item.trim())).collect()
+}
+
+/// Formats a list the way [`parse_list`] reads it.
+fn join_list<T>(items: &[T], format: impl Fn(&T) -> String) -> String {
+    items.iter().map(format).collect::<Vec<_>>().join(":")
+}
+
+/// Formats an optional path, empty if it isn't set.
+fn path_value(path: Option<&Path>) -> String {
+    path.map_or_else(String::new, |path| path.to_string_lossy().into_owned())
+}
+
+fn enum_name(names: &[(&'static str, c_int)], value: c_int) -> String {
+    names
+        .iter()
+        .find(|(_, v)| *v == value)
+        .map_or_else(|| value.to_string(), |(name, _)| (*name).to_owned())
+}
+
+fn enum_value(names: &[(&'static str, c_int)], name: &str) -> Option<c_int> {
+    names
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, v)| *v)
+        .or_else(|| name.parse().ok())
+}
+
+const COMPRESSION_TYPES: &[(&str, c_int)] = &[
+    ("kNoCompression", ffi::rocksdb_no_compression as c_int),
+    (
+        "kSnappyCompression",
+        ffi::rocksdb_snappy_compression as c_int,
+    ),
+    ("kZlibCompression", ffi::rocksdb_zlib_compression as c_int),
+    ("kBZip2Compression", ffi::rocksdb_bz2_compression as c_int),
+    ("kLZ4Compression", ffi::rocksdb_lz4_compression as c_int),
+    ("kLZ4HCCompression", ffi::rocksdb_lz4hc_compression as c_int),
+    (
+        "kXpressCompression",
+        ffi::rocksdb_xpress_compression as c_int,
+    ),
+    ("kZSTD", ffi::rocksdb_zstd_compression as c_int),
+    ("kDisableCompressionOption", 0xff),
+];
+
+const COMPACTION_STYLES: &[(&str, c_int)] = &[
+    (
+        "kCompactionStyleLevel",
+        ffi::rocksdb_level_compaction as c_int,
+    ),
+    (
+        "kCompactionStyleUniversal",
+        ffi::rocksdb_universal_compaction as c_int,
+    ),
+    (
+        "kCompactionStyleFIFO",
+        ffi::rocksdb_fifo_compaction as c_int,
+    ),
+];
+
+const RECOVERY_MODES: &[(&str, c_int)] = &[
+    (
+        "kTolerateCorruptedTailRecords",
+        ffi::rocksdb_tolerate_corrupted_tail_records_recovery as c_int,
+    ),
+    (
+        "kAbsoluteConsistency",
+        ffi::rocksdb_absolute_consistency_recovery as c_int,
+    ),
+    (
+        "kPointInTimeRecovery",
+        ffi::rocksdb_point_in_time_recovery as c_int,
+    ),
+    (
+        "kSkipAnyCorruptedRecords",
+        ffi::rocksdb_skip_any_corrupted_records_recovery as c_int,
+    ),
+];
+
+const ACCESS_HINTS: &[(&str, c_int)] = &[
+    ("NONE", 0),
+    ("NORMAL", 1),
+    ("SEQUENTIAL", 2),
+    ("WILLNEED", 3),
+];
+
+const INFO_LOG_LEVELS: &[(&str, c_int)] = &[
+    ("DEBUG_LEVEL", 0),
+    ("INFO_LEVEL", 1),
+    ("WARN_LEVEL", 2),
+    ("ERROR_LEVEL", 3),
+    ("FATAL_LEVEL", 4),
+    ("HEADER_LEVEL", 5),
+];
+
+/// Builds an [`OptionEntry`] for `Options` from a C API getter and setter.
+macro_rules! option {
+    ($name:expr, bool, $get:ident, $set:ident) => {
+        OptionEntry {
+            name: $name,
+            get: Some(|opts: &Options| (unsafe { ffi::$get(opts.inner) } != 0).to_string()),
+            set: |opts: &mut Options, value: &str| {
+                let value = parse_bool(value)?;
+                unsafe { ffi::$set(opts.inner, value as _) };
+                Some(())
+            },
+        }
+    };
+    ($name:expr, num, $get:ident, $set:ident) => {
+        OptionEntry {
+            name: $name,
+            get: Some(|opts: &Options| unsafe { ffi::$get(opts.inner) }.to_string()),
+            set: |opts: &mut Options, value: &str| {
+                unsafe { ffi::$set(opts.inner, parse_num(value)?) };
+                Some(())
+            },
+        }
+    };
+    ($name:expr, double, $get:ident, $set:ident) => {
+        OptionEntry {
+            name: $name,
+            get: Some(|opts: &Options| format!("{:.6}", unsafe { ffi::$get(opts.inner) })),
+            set: |opts: &mut Options, value: &str| {
+                unsafe { ffi::$set(opts.inner, parse_num(value)?) };
+                Some(())
+            },
+        }
+    };
+    ($name:expr, enum $names:ident, $get:ident, $set:ident) => {
+        OptionEntry {
+            name: $name,
+            get: Some(|opts: &Options| {
+                enum_name($names, unsafe { ffi::$get(opts.inner) } as c_int)
+            }),
+            set: |opts: &mut Options, value: &str| {
+                let value = enum_value($names, value)?;
+                unsafe { ffi::$set(opts.inner, value as _) };
+                Some(())
+            },
+        }
+    };
+}
+
+#[rustfmt::skip]
+pub(crate) const DB_OPTIONS: &[OptionEntry<Options>] = &[
+    option!("create_if_missing", bool, rocksdb_options_get_create_if_missing, rocksdb_options_set_create_if_missing),
+    option!("create_missing_column_families", bool, rocksdb_options_get_create_missing_column_families, rocksdb_options_set_create_missing_column_families),
+    option!("error_if_exists", bool, rocksdb_options_get_error_if_exists, rocksdb_options_set_error_if_exists),
+    option!("paranoid_checks", bool, rocksdb_options_get_paranoid_checks, rocksdb_options_set_paranoid_checks),
+    option!("info_log_level", enum INFO_LOG_LEVELS, rocksdb_options_get_info_log_level, rocksdb_options_set_info_log_level),
+    option!("db_write_buffer_size", num, rocksdb_options_get_db_write_buffer_size, rocksdb_options_set_db_write_buffer_size),
+    option!("max_open_files", num, rocksdb_options_get_max_open_files, rocksdb_options_set_max_open_files),
+    option!("max_file_opening_threads", num, rocksdb_options_get_max_file_opening_threads, rocksdb_options_set_max_file_opening_threads),
+    option!("max_total_wal_size", num, rocksdb_options_get_max_total_wal_size, rocksdb_options_set_max_total_wal_size),
+    option!("skip_stats_update_on_db_open", bool, rocksdb_options_get_skip_stats_update_on_db_open, rocksdb_options_set_skip_stats_update_on_db_open),
+    option!("skip_checking_sst_file_sizes_on_db_open", bool, rocksdb_options_get_skip_checking_sst_file_sizes_on_db_open, rocksdb_options_set_skip_checking_sst_file_sizes_on_db_open),
+    option!("enable_pipelined_write", bool, rocksdb_options_get_enable_pipelined_write, rocksdb_options_set_enable_pipelined_write),
+    option!("unordered_write", bool, rocksdb_options_get_unordered_write, rocksdb_options_set_unordered_write),
+    option!("max_subcompactions", num, rocksdb_options_get_max_subcompactions, rocksdb_options_set_max_subcompactions),
+    option!("max_background_jobs", num, rocksdb_options_get_max_background_jobs, rocksdb_options_set_max_background_jobs),
+    option!("max_background_compactions", num, rocksdb_options_get_max_background_compactions, rocksdb_options_set_max_background_compactions),
+    option!("base_background_compactions", num, rocksdb_options_get_base_background_compactions, rocksdb_options_set_base_background_compactions),
+    option!("max_background_flushes", num, rocksdb_options_get_max_background_flushes, rocksdb_options_set_max_background_flushes),
+    option!("max_log_file_size", num, rocksdb_options_get_max_log_file_size, rocksdb_options_set_max_log_file_size),
+    option!("log_file_time_to_roll", num, rocksdb_options_get_log_file_time_to_roll, rocksdb_options_set_log_file_time_to_roll),
+    option!("keep_log_file_num", num, rocksdb_options_get_keep_log_file_num, rocksdb_options_set_keep_log_file_num),
+    option!("recycle_log_file_num", num, rocksdb_options_get_recycle_log_file_num, rocksdb_options_set_recycle_log_file_num),
+    option!("max_manifest_file_size", num, rocksdb_options_get_max_manifest_file_size, rocksdb_options_set_max_manifest_file_size),
+    option!("table_cache_numshardbits", num, rocksdb_options_get_table_cache_numshardbits, rocksdb_options_set_table_cache_numshardbits),
+    option!("use_fsync", bool, rocksdb_options_get_use_fsync, rocksdb_options_set_use_fsync),
+    option!("WAL_ttl_seconds", num, rocksdb_options_get_WAL_ttl_seconds, rocksdb_options_set_WAL_ttl_seconds),
+    option!("WAL_size_limit_MB", num, rocksdb_options_get_WAL_size_limit_MB, rocksdb_options_set_WAL_size_limit_MB),
+    option!("manifest_preallocation_size", num, rocksdb_options_get_manifest_preallocation_size, rocksdb_options_set_manifest_preallocation_size),
+    option!("allow_mmap_reads", bool, rocksdb_options_get_allow_mmap_reads, rocksdb_options_set_allow_mmap_reads),
+    option!("allow_mmap_writes", bool, rocksdb_options_get_allow_mmap_writes, rocksdb_options_set_allow_mmap_writes),
+    option!("use_direct_reads", bool, rocksdb_options_get_use_direct_reads, rocksdb_options_set_use_direct_reads),
+    option!("use_direct_io_for_flush_and_compaction", bool, rocksdb_options_get_use_direct_io_for_flush_and_compaction, rocksdb_options_set_use_direct_io_for_flush_and_compaction),
+    option!("is_fd_close_on_exec", bool, rocksdb_options_get_is_fd_close_on_exec, rocksdb_options_set_is_fd_close_on_exec),
+    option!("skip_log_error_on_recovery", bool, rocksdb_options_get_skip_log_error_on_recovery, rocksdb_options_set_skip_log_error_on_recovery),
+    option!("stats_dump_period_sec", num, rocksdb_options_get_stats_dump_period_sec, rocksdb_options_set_stats_dump_period_sec),
+    option!("stats_persist_period_sec", num, rocksdb_options_get_stats_persist_period_sec, rocksdb_options_set_stats_persist_period_sec),
+    option!("advise_random_on_open", bool, rocksdb_options_get_advise_random_on_open, rocksdb_options_set_advise_random_on_open),
+    option!("access_hint_on_compaction_start", enum ACCESS_HINTS, rocksdb_options_get_access_hint_on_compaction_start, rocksdb_options_set_access_hint_on_compaction_start),
+    option!("use_adaptive_mutex", bool, rocksdb_options_get_use_adaptive_mutex, rocksdb_options_set_use_adaptive_mutex),
+    option!("bytes_per_sync", num, rocksdb_options_get_bytes_per_sync, rocksdb_options_set_bytes_per_sync),
+    option!("wal_bytes_per_sync", num, rocksdb_options_get_wal_bytes_per_sync, rocksdb_options_set_wal_bytes_per_sync),
+    option!("writable_file_max_buffer_size", num, rocksdb_options_get_writable_file_max_buffer_size, rocksdb_options_set_writable_file_max_buffer_size),
+    option!("allow_concurrent_memtable_write", bool, rocksdb_options_get_allow_concurrent_memtable_write, rocksdb_options_set_allow_concurrent_memtable_write),
+    option!("enable_write_thread_adaptive_yield", bool, rocksdb_options_get_enable_write_thread_adaptive_yield, rocksdb_options_set_enable_write_thread_adaptive_yield),
+    option!("delete_obsolete_files_period_micros", num, rocksdb_options_get_delete_obsolete_files_period_micros, rocksdb_options_set_delete_obsolete_files_period_micros),
+    option!("compaction_readahead_size", num, rocksdb_options_get_compaction_readahead_size, rocksdb_options_compaction_readahead_size),
+    option!("wal_recovery_mode", enum RECOVERY_MODES, rocksdb_options_get_wal_recovery_mode, rocksdb_options_set_wal_recovery_mode),
+    option!("allow_ingest_behind", bool, rocksdb_options_get_allow_ingest_behind, rocksdb_options_set_allow_ingest_behind),
+    option!("atomic_flush", bool, rocksdb_options_get_atomic_flush, rocksdb_options_set_atomic_flush),
+    OptionEntry {
+        name: "wal_dir",
+        get: Some(|opts| path_value(opts.get_wal_dir())),
+        set: |opts, value| {
+            if !value.is_empty() {
+                opts.set_wal_dir(value);
+            }
+            Some(())
+        },
+    },
+    OptionEntry {
+        name: "db_log_dir",
+        get: Some(|opts| path_value(opts.get_db_log_dir())),
+        set: |opts, value| {
+            if !value.is_empty() {
+                opts.set_db_log_dir(value);
+            }
+            Some(())
+        },
+    },
+    OptionEntry {
+        name: "manual_wal_flush",
+        get: Some(|opts| opts.get_manual_wal_flush().to_string()),
+        set: |opts, value| {
+            opts.set_manual_wal_flush(parse_bool(value)?);
+            Some(())
+        },
+    },
+    OptionEntry {
+        name: "dump_malloc_stats",
+        get: Some(|opts| opts.get_dump_malloc_stats().to_string()),
+        set: |opts, value| {
+            opts.set_dump_malloc_stats(parse_bool(value)?);
+            Some(())
+        },
+    },
+];
+
+#[rustfmt::skip]
+pub(crate) const CF_OPTIONS: &[OptionEntry<Options>] = &[
+    option!("write_buffer_size", num, rocksdb_options_get_write_buffer_size, rocksdb_options_set_write_buffer_size),
+    option!("max_write_buffer_number", num, rocksdb_options_get_max_write_buffer_number, rocksdb_options_set_max_write_buffer_number),
+    option!("min_write_buffer_number_to_merge", num, rocksdb_options_get_min_write_buffer_number_to_merge, rocksdb_options_set_min_write_buffer_number_to_merge),
+    option!("max_write_buffer_number_to_maintain", num, rocksdb_options_get_max_write_buffer_number_to_maintain, rocksdb_options_set_max_write_buffer_number_to_maintain),
+    option!("max_write_buffer_size_to_maintain", num, rocksdb_options_get_max_write_buffer_size_to_maintain, rocksdb_options_set_max_write_buffer_size_to_maintain),
+    option!("compression", enum COMPRESSION_TYPES, rocksdb_options_get_compression, rocksdb_options_set_compression),
+    option!("bottommost_compression", enum COMPRESSION_TYPES, rocksdb_options_get_bottommost_compression, rocksdb_options_set_bottommost_compression),
+    option!("compaction_style", enum COMPACTION_STYLES, rocksdb_options_get_compaction_style, rocksdb_options_set_compaction_style),
+    option!("num_levels", num, rocksdb_options_get_num_levels, rocksdb_options_set_num_levels),
+    option!("level0_file_num_compaction_trigger", num, rocksdb_options_get_level0_file_num_compaction_trigger, rocksdb_options_set_level0_file_num_compaction_trigger),
+    option!("level0_slowdown_writes_trigger", num, rocksdb_options_get_level0_slowdown_writes_trigger, rocksdb_options_set_level0_slowdown_writes_trigger),
+    option!("level0_stop_writes_trigger", num, rocksdb_options_get_level0_stop_writes_trigger, rocksdb_options_set_level0_stop_writes_trigger),
+    option!("target_file_size_base", num, rocksdb_options_get_target_file_size_base, rocksdb_options_set_target_file_size_base),
+    option!("target_file_size_multiplier", num, rocksdb_options_get_target_file_size_multiplier, rocksdb_options_set_target_file_size_multiplier),
+    option!("max_bytes_for_level_base", num, rocksdb_options_get_max_bytes_for_level_base, rocksdb_options_set_max_bytes_for_level_base),
+    option!("level_compaction_dynamic_level_bytes", bool, rocksdb_options_get_level_compaction_dynamic_level_bytes, rocksdb_options_set_level_compaction_dynamic_level_bytes),
+    option!("max_bytes_for_level_multiplier", double, rocksdb_options_get_max_bytes_for_level_multiplier, rocksdb_options_set_max_bytes_for_level_multiplier),
+    option!("max_compaction_bytes", num, rocksdb_options_get_max_compaction_bytes, rocksdb_options_set_max_compaction_bytes),
+    option!("soft_rate_limit", double, rocksdb_options_get_soft_rate_limit, rocksdb_options_set_soft_rate_limit),
+    option!("hard_rate_limit", double, rocksdb_options_get_hard_rate_limit, rocksdb_options_set_hard_rate_limit),
+    option!("soft_pending_compaction_bytes_limit", num, rocksdb_options_get_soft_pending_compaction_bytes_limit, rocksdb_options_set_soft_pending_compaction_bytes_limit),
+    option!("hard_pending_compaction_bytes_limit", num, rocksdb_options_get_hard_pending_compaction_bytes_limit, rocksdb_options_set_hard_pending_compaction_bytes_limit),
+    option!("rate_limit_delay_max_milliseconds", num, rocksdb_options_get_rate_limit_delay_max_milliseconds, rocksdb_options_set_rate_limit_delay_max_milliseconds),
+    option!("arena_block_size", num, rocksdb_options_get_arena_block_size, rocksdb_options_set_arena_block_size),
+    option!("disable_auto_compactions", bool, rocksdb_options_get_disable_auto_compactions, rocksdb_options_set_disable_auto_compactions),
+    option!("max_sequential_skip_in_iterations", num, rocksdb_options_get_max_sequential_skip_in_iterations, rocksdb_options_set_max_sequential_skip_in_iterations),
+    option!("optimize_filters_for_hits", bool, rocksdb_options_get_optimize_filters_for_hits, rocksdb_options_set_optimize_filters_for_hits),
+    option!("memtable_prefix_bloom_size_ratio", double, rocksdb_options_get_memtable_prefix_bloom_size_ratio, rocksdb_options_set_memtable_prefix_bloom_size_ratio),
+    option!("memtable_huge_page_size", num, rocksdb_options_get_memtable_huge_page_size, rocksdb_options_set_memtable_huge_page_size),
+    option!("max_successive_merges", num, rocksdb_options_get_max_successive_merges, rocksdb_options_set_max_successive_merges),
+    option!("bloom_locality", num, rocksdb_options_get_bloom_locality, rocksdb_options_set_bloom_locality),
+    option!("inplace_update_support", bool, rocksdb_options_get_inplace_update_support, rocksdb_options_set_inplace_update_support),
+    option!("inplace_update_num_locks", num, rocksdb_options_get_inplace_update_num_locks, rocksdb_options_set_inplace_update_num_locks),
+    option!("report_bg_io_stats", bool, rocksdb_options_get_report_bg_io_stats, rocksdb_options_set_report_bg_io_stats),
+    option!("enable_blob_files", bool, rocksdb_options_get_enable_blob_files, rocksdb_options_set_enable_blob_files),
+    option!("min_blob_size", num, rocksdb_options_get_min_blob_size, rocksdb_options_set_min_blob_size),
+    option!("blob_file_size", num, rocksdb_options_get_blob_file_size, rocksdb_options_set_blob_file_size),
+    option!("blob_compression_type", enum COMPRESSION_TYPES, rocksdb_options_get_blob_compression_type, rocksdb_options_set_blob_compression_type),
+    option!("enable_blob_garbage_collection", bool, rocksdb_options_get_enable_blob_gc, rocksdb_options_set_enable_blob_gc),
+    option!("blob_garbage_collection_age_cutoff", double, rocksdb_options_get_blob_gc_age_cutoff, rocksdb_options_set_blob_gc_age_cutoff),
+    OptionEntry {
+        name: "compression_per_level",
+        get: Some(|opts| {
+            let levels = opts.get_compression_per_level().unwrap_or_default();
+            join_list(levels, |&level| enum_name(COMPRESSION_TYPES, level as c_int))
+        }),
+        set: |opts, value| {
+            let levels = parse_list(value, |item| {
+                DBCompressionType::from_raw(enum_value(COMPRESSION_TYPES, item)?)
+            })?;
+            if !levels.is_empty() {
+                opts.set_compression_per_level(&levels);
+            }
+            Some(())
+        },
+    },
+    OptionEntry {
+        name: "max_bytes_for_level_multiplier_additional",
+        get: Some(|opts| {
+            let levels = opts
+                .get_max_bytes_for_level_multiplier_additional()
+                .unwrap_or_default();
+            join_list(levels, i32::to_string)
+        }),
+        set: |opts, value| {
+            let levels = parse_list(value, parse_num)?;
+            if !levels.is_empty() {
+                opts.set_max_bytes_for_level_multiplier_additional(&levels);
+            }
+            Some(())
+        },
+    },
+    OptionEntry {
+        name: "ttl",
+        get: Some(|opts| opts.get_ttl().unwrap_or(DEFAULT_SECONDS).to_string()),
+        set: |opts, value| match parse_num(value)? {
+            DEFAULT_SECONDS => Some(()),
+            secs => opts.set_ttl(secs).ok(),
+        },
+    },
+    OptionEntry {
+        name: "periodic_compaction_seconds",
+        get: Some(|opts| {
+            opts.get_periodic_compaction_seconds()
+                .unwrap_or(DEFAULT_SECONDS)
+                .to_string()
+        }),
+        set: |opts, value| match parse_num(value)? {
+            DEFAULT_SECONDS => Some(()),
+            secs => opts.set_periodic_compaction_seconds(secs).ok(),
+        },
+    },
+    OptionEntry {
+        name: "sample_for_compression",
+        get: Some(|opts| opts.get_sample_for_compression().unwrap_or(0).to_string()),
+        set: |opts, value| opts.set_sample_for_compression(parse_num(value)?).ok(),
+    },
+    OptionEntry {
+        name: "memtable_whole_key_filtering",
+        get: None,
+        set: |opts, value| {
+            opts.set_memtable_whole_key_filtering(parse_bool(value)?);
+            Some(())
+        },
+    },
+];
+
+/// Builds an [`OptionEntry`] for `BlockBasedOptions` from a C API setter.
+macro_rules! block_based_option {
+    ($name:expr, bool, $set:ident) => {
+        OptionEntry {
+            name: $name,
+            get: None,
+            set: |opts: &mut BlockBasedOptions, value: &str| {
+                let value = parse_bool(value)?;
+                unsafe { ffi::$set(opts.inner, value as c_uchar) };
+                opts.record($name, value);
+                Some(())
+            },
+        }
+    };
+    ($name:expr, num, $set:ident) => {
+        OptionEntry {
+            name: $name,
+            get: None,
+            set: |opts: &mut BlockBasedOptions, value: &str| {
+                let value = parse_num(value)?;
+                unsafe { ffi::$set(opts.inner, value) };
+                opts.record($name, value);
+                Some(())
+            },
+        }
+    };
+}
+
+#[rustfmt::skip]
+const BLOCK_BASED_TABLE_OPTIONS: &[OptionEntry<BlockBasedOptions>] = &[
+    block_based_option!("block_size", num, rocksdb_block_based_options_set_block_size),
+    block_based_option!("block_size_deviation", num, rocksdb_block_based_options_set_block_size_deviation),
+    block_based_option!("block_restart_interval", num, rocksdb_block_based_options_set_block_restart_interval),
+    block_based_option!("index_block_restart_interval", num, rocksdb_block_based_options_set_index_block_restart_interval),
+    block_based_option!("metadata_block_size", num, rocksdb_block_based_options_set_metadata_block_size),
+    block_based_option!("format_version", num, rocksdb_block_based_options_set_format_version),
+    block_based_option!("partition_filters", bool, rocksdb_block_based_options_set_partition_filters),
+    block_based_option!("use_delta_encoding", bool, rocksdb_block_based_options_set_use_delta_encoding),
+    block_based_option!("no_block_cache", bool, rocksdb_block_based_options_set_no_block_cache),
+    block_based_option!("whole_key_filtering", bool, rocksdb_block_based_options_set_whole_key_filtering),
+    block_based_option!("hash_index_allow_collision", bool, rocksdb_block_based_options_set_hash_index_allow_collision),
+    block_based_option!("cache_index_and_filter_blocks", bool, rocksdb_block_based_options_set_cache_index_and_filter_blocks),
+    block_based_option!("cache_index_and_filter_blocks_with_high_priority", bool, rocksdb_block_based_options_set_cache_index_and_filter_blocks_with_high_priority),
+    block_based_option!("pin_l0_filter_and_index_blocks_in_cache", bool, rocksdb_block_based_options_set_pin_l0_filter_and_index_blocks_in_cache),
+    block_based_option!("pin_top_level_index_and_filter", bool, rocksdb_block_based_options_set_pin_top_level_index_and_filter),
+    block_based_option!("data_block_hash_table_util_ratio", num, rocksdb_block_based_options_set_data_block_hash_ratio),
+    OptionEntry {
+        name: "index_type",
+        get: None,
+        set: |opts, value| {
+            opts.set_index_type(match value {
+                "kBinarySearch" => BlockBasedIndexType::BinarySearch,
+                "kHashSearch" => BlockBasedIndexType::HashSearch,
+                "kTwoLevelIndexSearch" => BlockBasedIndexType::TwoLevelIndexSearch,
+                _ => return None,
+            });
+            Some(())
+        },
+    },
+    OptionEntry {
+        name: "data_block_index_type",
+        get: None,
+        set: |opts, value| {
+            opts.set_data_block_index_type(match value {
+                "kDataBlockBinarySearch" => DataBlockIndexType::BinarySearch,
+                "kDataBlockBinaryAndHash" => DataBlockIndexType::BinaryAndHash,
+                _ => return None,
+            });
+            Some(())
+        },
+    },
+    OptionEntry {
+        name: "filter_policy",
+        get: None,
+        // RocksDB only records the name of the filter policy, so the bits per
+        // key are lost and a full bloom filter with its default of 10 is used
+        // unless the loader has a filter policy registered under that name.
+        set: |opts, value| {
+            match value {
+                NULLPTR => {}
+                "rocksdb.BuiltinBloomFilter" => opts.set_bloom_filter(10, false),
+                _ => {
+                    let mut parts = value.strip_prefix("bloomfilter:")?.split(':');
+                    let bits_per_key = parse_num(parts.next()?)?;
+                    let block_based = parse_bool(parts.next()?)?;
+                    opts.set_bloom_filter(bits_per_key, block_based);
+                }
+            }
+            Some(())
+        },
+    },
+];
+
+#[rustfmt::skip]
+const PLAIN_TABLE_OPTIONS: &[OptionEntry<PlainTableFactoryOptions>] = &[
+    OptionEntry {
+        name: "user_key_len",
+        get: None,
+        set: |opts, value| {
+            opts.user_key_length = parse_num(value)?;
+            Some(())
+        },
+    },
+    OptionEntry {
+        name: "bloom_bits_per_key",
+        get: None,
+        set: |opts, value| {
+            opts.bloom_bits_per_key = parse_num(value)?;
+            Some(())
+        },
+    },
+    OptionEntry {
+        name: "hash_table_ratio",
+        get: None,
+        set: |opts, value| {
+            opts.hash_table_ratio = parse_num(value)?;
+            Some(())
+        },
+    },
+    OptionEntry {
+        name: "index_sparseness",
+        get: None,
+        set: |opts, value| {
+            opts.index_sparseness = parse_num(value)?;
+            Some(())
+        },
+    },
+];
+
+#[rustfmt::skip]
+const CUCKOO_TABLE_OPTIONS: &[OptionEntry<CuckooTableOptions>] = &[
+    OptionEntry {
+        name: "hash_table_ratio",
+        get: None,
+        set: |opts, value| {
+            opts.set_hash_ratio(parse_num(value)?);
+            Some(())
+        },
+    },
+    OptionEntry {
+        name: "max_search_depth",
+        get: None,
+        set: |opts, value| {
+            opts.set_max_search_depth(parse_num(value)?);
+            Some(())
+        },
+    },
+    OptionEntry {
+        name: "cuckoo_block_size",
+        get: None,
+        set: |opts, value| {
+            opts.set_cuckoo_block_size(parse_num(value)?);
+            Some(())
+        },
+    },
+    OptionEntry {
+        name: "identity_as_first_hash",
+        get: None,
+        set: |opts, value| {
+            opts.set_identity_as_first_hash(parse_bool(value)?);
+            Some(())
+        },
+    },
+    OptionEntry {
+        name: "use_module_hash",
+        get: None,
+        set: |opts, value| {
+            opts.set_use_module_hash(parse_bool(value)?);
+            Some(())
+        },
+    },
+];
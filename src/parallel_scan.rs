@@ -0,0 +1,308 @@
+// Copyright 2021 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    db::{DBWithThreadMode, ThreadMode},
+    db_iterator::KVBytes,
+    ffi, AsColumnFamilyRef, Error, IteratorMode, OwnedIterator, OwnedSnapshot, Range, ReadOptions,
+};
+
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+/// A half-open range of keys `[start, end)` scanned by one shard of a
+/// [`ParallelScanner`]. `None` means the range is unbounded on that side.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyRange {
+    pub start: Option<Vec<u8>>,
+    pub end: Option<Vec<u8>>,
+}
+
+/// Scans a column family with several threads.
+///
+/// The key space is split into shards of roughly equal size, using the
+/// boundaries of the SST files and the approximate size of the data between
+/// them. Every shard is scanned by its own iterator, all reading from the same
+/// snapshot, so the shards together form a consistent view of the column
+/// family. Data that is only in memtables isn't taken into account when
+/// splitting, so shards may be unbalanced if it isn't flushed first.
+///
+/// ```
+/// use rocksdb::{ParallelScanner, Options, DB};
+/// use std::sync::Arc;
+///
+/// let path = "_path_for_rocksdb_storage_parallel_scan";
+/// {
+///     let db = Arc::new(DB::open_default(path).unwrap());
+///     for i in 0..1000u32 {
+///         db.put(i.to_be_bytes(), b"value").unwrap();
+///     }
+///     db.flush().unwrap();
+///
+///     let scanner = ParallelScanner::new(db.clone(), 4);
+///     let counts = scanner.scan(|_shard, _range, iter| iter.count()).unwrap();
+///     assert_eq!(counts.iter().sum::<usize>(), 1000);
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+pub struct ParallelScanner<T: ThreadMode + 'static> {
+    db: Arc<DBWithThreadMode<T>>,
+    shards: usize,
+    threads: usize,
+    fill_cache: bool,
+}
+
+impl<T: ThreadMode + 'static> ParallelScanner<T> {
+    /// Creates a scanner which splits the key space into at most `shards`
+    /// shards, scanned by as many threads.
+    pub fn new(db: Arc<DBWithThreadMode<T>>, shards: usize) -> Self {
+        Self {
+            db,
+            shards: shards.max(1),
+            threads: shards.max(1),
+            fill_cache: false,
+        }
+    }
+
+    /// Sets the number of threads scanning the shards. Shards are handed to
+    /// the threads in order as they become idle.
+    ///
+    /// Default: the number of shards
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Sets whether the data read by the scan should be put in the block cache.
+    ///
+    /// Default: false
+    pub fn set_fill_cache(&mut self, fill_cache: bool) {
+        self.fill_cache = fill_cache;
+    }
+
+    /// Returns the key ranges of the shards of the default column family.
+    pub fn shards(&self) -> Result<Vec<KeyRange>, Error> {
+        self.split(None)
+    }
+
+    /// Returns the key ranges of the shards of the column family.
+    pub fn shards_cf(&self, cf: &impl AsColumnFamilyRef) -> Result<Vec<KeyRange>, Error> {
+        self.split(Some(RawColumnFamily(cf.inner())))
+    }
+
+    /// Scans the default column family, calling `f` with the index, the key
+    /// range and an iterator over each shard.
+    ///
+    /// Returns the results of `f` in shard order once all shards are scanned.
+    /// The iterators can't be returned from `f`, see [`ShardIterator`].
+    pub fn scan<F, R>(&self, f: F) -> Result<Vec<R>, Error>
+    where
+        F: for<'s> Fn(usize, &'s KeyRange, ShardIterator<'s, T>) -> R + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        self.run(None, f)
+    }
+
+    /// Scans the column family, calling `f` with the index, the key range and
+    /// an iterator over each shard.
+    ///
+    /// Returns the results of `f` in shard order once all shards are scanned.
+    /// The iterators can't be returned from `f`, see [`ShardIterator`].
+    pub fn scan_cf<F, R>(&self, cf: &impl AsColumnFamilyRef, f: F) -> Result<Vec<R>, Error>
+    where
+        F: for<'s> Fn(usize, &'s KeyRange, ShardIterator<'s, T>) -> R + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        self.run(Some(RawColumnFamily(cf.inner())), f)
+    }
+
+    fn split(&self, cf: Option<RawColumnFamily>) -> Result<Vec<KeyRange>, Error> {
+        let full = vec![KeyRange {
+            start: None,
+            end: None,
+        }];
+
+        let mut keys: Vec<Vec<u8>> = self
+            .db
+            .live_files()?
+            .into_iter()
+            .flat_map(|file| file.start_key.into_iter().chain(file.end_key))
+            .collect();
+        keys.sort();
+        keys.dedup();
+        if self.shards == 1 || keys.len() < 2 {
+            return Ok(full);
+        }
+
+        // Live files include every column family, so their boundaries are only
+        // candidates; the sizes are measured in the scanned column family.
+        let ranges: Vec<_> = keys
+            .windows(2)
+            .map(|pair| Range::new(&pair[0], &pair[1]))
+            .collect();
+        let sizes = match &cf {
+            Some(cf) => self.db.get_approximate_sizes_cf(cf, &ranges)?,
+            None => self.db.get_approximate_sizes(&ranges)?,
+        };
+        let total: u128 = sizes.iter().map(|&size| u128::from(size)).sum();
+        if total == 0 {
+            return Ok(full);
+        }
+
+        let mut boundaries: Vec<Vec<u8>> = Vec::new();
+        let mut scanned: u128 = 0;
+        for (i, &size) in sizes.iter().enumerate() {
+            scanned += u128::from(size);
+            let next_shard = boundaries.len() as u128 + 1;
+            if next_shard < self.shards as u128
+                && scanned * self.shards as u128 >= total * next_shard
+            {
+                // keys[i + 1] is the end of the i-th range.
+                boundaries.push(keys[i + 1].clone());
+            }
+        }
+
+        let mut shards = Vec::with_capacity(boundaries.len() + 1);
+        let mut start = None;
+        for boundary in boundaries {
+            shards.push(KeyRange {
+                start: start.take(),
+                end: Some(boundary.clone()),
+            });
+            start = Some(boundary);
+        }
+        shards.push(KeyRange { start, end: None });
+        Ok(shards)
+    }
+
+    fn run<F, R>(&self, cf: Option<RawColumnFamily>, f: F) -> Result<Vec<R>, Error>
+    where
+        F: for<'s> Fn(usize, &'s KeyRange, ShardIterator<'s, T>) -> R + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        let shards = Arc::new(self.split(cf)?);
+        let snapshot = OwnedSnapshot::new(self.db.clone());
+        let next_shard = Arc::new(AtomicUsize::new(0));
+        let f = Arc::new(f);
+        let (tx, rx) = mpsc::channel();
+
+        let mut workers = Vec::new();
+        let mut spawn_error = None;
+        for _ in 0..self.threads.min(shards.len()) {
+            let shards = shards.clone();
+            let snapshot = snapshot.clone();
+            let next_shard = next_shard.clone();
+            let f = f.clone();
+            let tx = tx.clone();
+            let fill_cache = self.fill_cache;
+            let spawned = thread::Builder::new()
+                .name("rocksdb-scan".to_owned())
+                .spawn(move || loop {
+                    let index = next_shard.fetch_add(1, Ordering::SeqCst);
+                    let range = match shards.get(index) {
+                        Some(range) => range,
+                        None => return,
+                    };
+
+                    let mut readopts = ReadOptions::default();
+                    readopts.fill_cache(fill_cache);
+                    if let Some(start) = &range.start {
+                        readopts.set_iterate_lower_bound(start.clone());
+                    }
+                    if let Some(end) = &range.end {
+                        readopts.set_iterate_upper_bound(end.clone());
+                    }
+                    let iter = ShardIterator {
+                        iter: match &cf {
+                            Some(cf) => snapshot.iterator_cf_opt(cf, readopts, IteratorMode::Start),
+                            None => snapshot.iterator_opt(IteratorMode::Start, readopts),
+                        },
+                        _scan: PhantomData,
+                    };
+
+                    let _ = tx.send((index, f(index, range, iter)));
+                });
+            match spawned {
+                Ok(worker) => workers.push(worker),
+                Err(e) => {
+                    spawn_error = Some(Error::new(format!("Failed to spawn scan thread: {}", e)));
+                    break;
+                }
+            }
+        }
+        drop(tx);
+
+        let mut results: Vec<Option<R>> = (0..shards.len()).map(|_| None).collect();
+        for (index, result) in rx {
+            results[index] = Some(result);
+        }
+        let mut panicked = false;
+        for worker in workers {
+            panicked |= worker.join().is_err();
+        }
+        if let Some(e) = spawn_error {
+            return Err(e);
+        }
+        if panicked {
+            return Err(Error::new("A scan thread panicked".to_owned()));
+        }
+        Ok(results.into_iter().map(|result| result.unwrap()).collect())
+    }
+}
+
+/// An iterator over one shard of a [`ParallelScanner`].
+///
+/// It can't be returned from the closure it is passed to, since it may read
+/// from a column family handle which is only borrowed for the scan.
+pub struct ShardIterator<'s, T: ThreadMode + 'static> {
+    iter: OwnedIterator<T>,
+    _scan: PhantomData<&'s ()>,
+}
+
+impl<'s, T: ThreadMode + 'static> ShardIterator<'s, T> {
+    /// See [`valid`](crate::DBRawIteratorWithThreadMode::valid)
+    pub fn valid(&self) -> bool {
+        self.iter.valid()
+    }
+
+    /// See [`status`](crate::DBRawIteratorWithThreadMode::status)
+    pub fn status(&self) -> Result<(), Error> {
+        self.iter.status()
+    }
+}
+
+impl<'s, T: ThreadMode + 'static> Iterator for ShardIterator<'s, T> {
+    type Item = KVBytes;
+
+    fn next(&mut self) -> Option<KVBytes> {
+        self.iter.next()
+    }
+}
+
+/// A column family handle borrowed for the duration of a scan.
+#[derive(Clone, Copy)]
+struct RawColumnFamily(*mut ffi::rocksdb_column_family_handle_t);
+
+impl AsColumnFamilyRef for RawColumnFamily {
+    fn inner(&self) -> *mut ffi::rocksdb_column_family_handle_t {
+        self.0
+    }
+}
+
+// The handle outlives the scan since `run` joins all threads before returning,
+// and the iterators using it can't escape the scan, see `ShardIterator`.
+// RocksDB allows column family handles to be used concurrently.
+unsafe impl Send for RawColumnFamily {}
+unsafe impl Sync for RawColumnFamily {}
@@ -14,7 +14,7 @@
 
 use libc::{c_int, c_uchar, c_void};
 
-use crate::{ffi, ffi_util::from_cstr, Cache, Error, DB};
+use crate::{ffi, ffi_util::from_cstr, Cache, DBWithThreadMode, Error, ThreadMode, DB};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 #[repr(i32)]
@@ -240,7 +240,7 @@ impl MemoryUsageBuilder {
     }
 
     /// Add a DB instance to collect memory usage from it and add up in total stats
-    fn add_db(&mut self, db: &DB) {
+    fn add_db<T: ThreadMode>(&mut self, db: &DBWithThreadMode<T>) {
         unsafe {
             ffi::rocksdb_memory_consumers_add_db(self.inner, db.inner);
         }
@@ -260,6 +260,17 @@ impl MemoryUsageBuilder {
             Ok(MemoryUsage { inner: mu })
         }
     }
+
+    /// Build up MemoryUsage and read its stats
+    fn stats(&self) -> Result<MemoryUsageStats, Error> {
+        let mu = self.build()?;
+        Ok(MemoryUsageStats {
+            mem_table_total: mu.approximate_mem_table_total(),
+            mem_table_unflushed: mu.approximate_mem_table_unflushed(),
+            mem_table_readers_total: mu.approximate_mem_table_readers_total(),
+            cache_total: mu.approximate_cache_total(),
+        })
+    }
 }
 
 /// Get memory usage stats from DB instances and Cache instances
@@ -275,11 +286,15 @@ pub fn get_memory_usage_stats(
         caches_.iter().for_each(|cache| builder.add_cache(cache));
     }
 
-    let mu = builder.build()?;
-    Ok(MemoryUsageStats {
-        mem_table_total: mu.approximate_mem_table_total(),
-        mem_table_unflushed: mu.approximate_mem_table_unflushed(),
-        mem_table_readers_total: mu.approximate_mem_table_readers_total(),
-        cache_total: mu.approximate_cache_total(),
-    })
+    builder.stats()
+}
+
+/// Get memory usage stats from a single DB instance of any thread mode
+#[cfg(feature = "metrics")]
+pub(crate) fn get_db_memory_usage_stats<T: ThreadMode>(
+    db: &DBWithThreadMode<T>,
+) -> Result<MemoryUsageStats, Error> {
+    let mut builder = MemoryUsageBuilder::new()?;
+    builder.add_db(db);
+    builder.stats()
 }
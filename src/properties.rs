@@ -0,0 +1,532 @@
+// Copyright 2021 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Names of the properties exposed by RocksDB, and parsers for the structured ones.
+//!
+//! The constants can be passed to the typed getters of [`DB`](crate::DB),
+//! such as [`int_property`](crate::DBWithThreadMode::int_property), which only accept a
+//! [`Property`], so a misspelled property is a compile error rather than a
+//! `None` at runtime. They also reject properties whose value doesn't have the
+//! requested type. The string-based getters such as `property_int_value`
+//! accept the constants too.
+//!
+//! ```
+//! use rocksdb::{properties, DB};
+//!
+//! let path = "_path_for_rocksdb_storage_properties";
+//! {
+//!     let db = DB::open_default(path).unwrap();
+//!     db.put(b"key", b"value").unwrap();
+//!     db.flush().unwrap();
+//!
+//!     let keys = db.int_property(&properties::ESTIMATE_NUM_KEYS).unwrap();
+//!     assert_eq!(keys, Some(1));
+//!     assert!(db.int_property(&properties::LEVELSTATS).is_err());
+//!
+//!     let stats: properties::LevelStats = db
+//!         .string_property(&properties::LEVELSTATS)
+//!         .unwrap()
+//!         .unwrap()
+//!         .parse()
+//!         .unwrap();
+//!     assert_eq!(stats.levels[0].files, 1);
+//! }
+//! let _ = DB::destroy(&rocksdb::Options::default(), path);
+//! ```
+
+use crate::Error;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// The type of the value of a property.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PropertyType {
+    /// An integer, which can be read with `property_int_value`.
+    Int,
+    /// A free-form, usually multi-line, string.
+    String,
+    /// A string made of `name=value` pairs, which can be read with
+    /// `property_map_value` or parsed into one of the structs of this module.
+    Map,
+}
+
+/// The name of a RocksDB property along with the type of its value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Property {
+    name: Cow<'static, str>,
+    value_type: PropertyType,
+}
+
+impl Property {
+    const fn new(name: &'static str, value_type: PropertyType) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            value_type,
+        }
+    }
+
+    fn at_level(prefix: &str, level: usize, value_type: PropertyType) -> Self {
+        Self {
+            name: Cow::Owned(format!("{}{}", prefix, level)),
+            value_type,
+        }
+    }
+
+    /// Returns the name of the property, e.g. `rocksdb.estimate-num-keys`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the type of the value of the property.
+    pub fn value_type(&self) -> PropertyType {
+        self.value_type
+    }
+
+    /// Fails unless the value of the property is of type `expected`.
+    pub(crate) fn expect_type(&self, expected: PropertyType) -> Result<(), Error> {
+        if self.value_type == expected {
+            Ok(())
+        } else {
+            Err(Error::new(format!(
+                "Property {} has a value of type {:?}, not {:?}",
+                self.name, self.value_type, expected
+            )))
+        }
+    }
+}
+
+impl AsRef<str> for Property {
+    fn as_ref(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Name of the [`num_files_at_level`] properties, without the level.
+pub(crate) const NUM_FILES_AT_LEVEL_PREFIX: &str = "rocksdb.num-files-at-level";
+
+/// Number of files at level `level`.
+pub fn num_files_at_level(level: usize) -> Property {
+    Property::at_level(NUM_FILES_AT_LEVEL_PREFIX, level, PropertyType::Int)
+}
+
+/// Compression ratio of the data at level `level`, defined as uncompressed
+/// data size / compressed file size. `-1.0` if there are no open files at
+/// the level.
+pub fn compression_ratio_at_level(level: usize) -> Property {
+    Property::at_level(
+        "rocksdb.compression-ratio-at-level",
+        level,
+        PropertyType::String,
+    )
+}
+
+/// Aggregated table properties of the files at level `level`, see
+/// [`TableProperties`].
+pub fn aggregated_table_properties_at_level(level: usize) -> Property {
+    Property::at_level(
+        "rocksdb.aggregated-table-properties-at-level",
+        level,
+        PropertyType::Map,
+    )
+}
+
+/// The data of [`CFSTATS`] followed by the data of [`DBSTATS`].
+pub const STATS: Property = Property::new("rocksdb.stats", PropertyType::String);
+/// A summary of the current SST files.
+pub const SSTABLES: Property = Property::new("rocksdb.sstables", PropertyType::String);
+/// The data of [`CFSTATS_NO_FILE_HISTOGRAM`] and [`CF_FILE_HISTOGRAM`], see
+/// [`CfStats`].
+pub const CFSTATS: Property = Property::new("rocksdb.cfstats", PropertyType::String);
+/// Column family stats per level over the lifetime of the database, see
+/// [`CfStats`].
+pub const CFSTATS_NO_FILE_HISTOGRAM: Property =
+    Property::new("rocksdb.cfstats-no-file-histogram", PropertyType::String);
+/// Number of file reads per level and the latency histogram of single reads.
+pub const CF_FILE_HISTOGRAM: Property =
+    Property::new("rocksdb.cf-file-histogram", PropertyType::String);
+/// General database stats, both cumulative and since the last retrieval.
+pub const DBSTATS: Property = Property::new("rocksdb.dbstats", PropertyType::String);
+/// Number of files and total size of each level, see [`LevelStats`].
+pub const LEVELSTATS: Property = Property::new("rocksdb.levelstats", PropertyType::String);
+/// Number of immutable memtables that have not yet been flushed.
+pub const NUM_IMMUTABLE_MEM_TABLE: Property =
+    Property::new("rocksdb.num-immutable-mem-table", PropertyType::Int);
+/// Number of immutable memtables that have already been flushed.
+pub const NUM_IMMUTABLE_MEM_TABLE_FLUSHED: Property =
+    Property::new("rocksdb.num-immutable-mem-table-flushed", PropertyType::Int);
+/// 1 if a memtable flush is pending, 0 otherwise.
+pub const MEM_TABLE_FLUSH_PENDING: Property =
+    Property::new("rocksdb.mem-table-flush-pending", PropertyType::Int);
+/// Number of currently running flushes.
+pub const NUM_RUNNING_FLUSHES: Property =
+    Property::new("rocksdb.num-running-flushes", PropertyType::Int);
+/// 1 if at least one compaction is pending, 0 otherwise.
+pub const COMPACTION_PENDING: Property =
+    Property::new("rocksdb.compaction-pending", PropertyType::Int);
+/// Number of currently running compactions.
+pub const NUM_RUNNING_COMPACTIONS: Property =
+    Property::new("rocksdb.num-running-compactions", PropertyType::Int);
+/// Accumulated number of background errors.
+pub const BACKGROUND_ERRORS: Property =
+    Property::new("rocksdb.background-errors", PropertyType::Int);
+/// Approximate size of the active memtable in bytes.
+pub const CUR_SIZE_ACTIVE_MEM_TABLE: Property =
+    Property::new("rocksdb.cur-size-active-mem-table", PropertyType::Int);
+/// Approximate size of the active and unflushed immutable memtables in bytes.
+pub const CUR_SIZE_ALL_MEM_TABLES: Property =
+    Property::new("rocksdb.cur-size-all-mem-tables", PropertyType::Int);
+/// Approximate size of the active, unflushed immutable and pinned immutable
+/// memtables in bytes.
+pub const SIZE_ALL_MEM_TABLES: Property =
+    Property::new("rocksdb.size-all-mem-tables", PropertyType::Int);
+/// Number of entries in the active memtable.
+pub const NUM_ENTRIES_ACTIVE_MEM_TABLE: Property =
+    Property::new("rocksdb.num-entries-active-mem-table", PropertyType::Int);
+/// Number of entries in the unflushed immutable memtables.
+pub const NUM_ENTRIES_IMM_MEM_TABLES: Property =
+    Property::new("rocksdb.num-entries-imm-mem-tables", PropertyType::Int);
+/// Number of delete entries in the active memtable.
+pub const NUM_DELETES_ACTIVE_MEM_TABLE: Property =
+    Property::new("rocksdb.num-deletes-active-mem-table", PropertyType::Int);
+/// Number of delete entries in the unflushed immutable memtables.
+pub const NUM_DELETES_IMM_MEM_TABLES: Property =
+    Property::new("rocksdb.num-deletes-imm-mem-tables", PropertyType::Int);
+/// Estimated number of keys in the memtables and storage.
+pub const ESTIMATE_NUM_KEYS: Property =
+    Property::new("rocksdb.estimate-num-keys", PropertyType::Int);
+/// Estimated memory used for reading SST files, excluding the block cache.
+pub const ESTIMATE_TABLE_READERS_MEM: Property =
+    Property::new("rocksdb.estimate-table-readers-mem", PropertyType::Int);
+/// 0 if deletion of obsolete files is enabled, non-zero otherwise.
+pub const IS_FILE_DELETIONS_ENABLED: Property =
+    Property::new("rocksdb.is-file-deletions-enabled", PropertyType::Int);
+/// Number of unreleased snapshots.
+pub const NUM_SNAPSHOTS: Property = Property::new("rocksdb.num-snapshots", PropertyType::Int);
+/// Unix timestamp of the oldest unreleased snapshot.
+pub const OLDEST_SNAPSHOT_TIME: Property =
+    Property::new("rocksdb.oldest-snapshot-time", PropertyType::Int);
+/// Sequence number of the oldest unreleased snapshot.
+pub const OLDEST_SNAPSHOT_SEQUENCE: Property =
+    Property::new("rocksdb.oldest-snapshot-sequence", PropertyType::Int);
+/// Number of live versions of the LSM tree.
+pub const NUM_LIVE_VERSIONS: Property =
+    Property::new("rocksdb.num-live-versions", PropertyType::Int);
+/// Number of the current LSM version, incremented on every change to the tree.
+pub const CURRENT_SUPER_VERSION_NUMBER: Property =
+    Property::new("rocksdb.current-super-version-number", PropertyType::Int);
+/// Estimated amount of live data in bytes.
+pub const ESTIMATE_LIVE_DATA_SIZE: Property =
+    Property::new("rocksdb.estimate-live-data-size", PropertyType::Int);
+/// Minimum number of the log files that should be kept.
+pub const MIN_LOG_NUMBER_TO_KEEP: Property =
+    Property::new("rocksdb.min-log-number-to-keep", PropertyType::Int);
+/// Minimum file number of the obsolete SST files that should be kept.
+pub const MIN_OBSOLETE_SST_NUMBER_TO_KEEP: Property =
+    Property::new("rocksdb.min-obsolete-sst-number-to-keep", PropertyType::Int);
+/// Total size of all SST files in bytes.
+pub const TOTAL_SST_FILES_SIZE: Property =
+    Property::new("rocksdb.total-sst-files-size", PropertyType::Int);
+/// Total size of the SST files of the latest LSM tree in bytes.
+pub const LIVE_SST_FILES_SIZE: Property =
+    Property::new("rocksdb.live-sst-files-size", PropertyType::Int);
+/// Level to which L0 data is compacted.
+pub const BASE_LEVEL: Property = Property::new("rocksdb.base-level", PropertyType::Int);
+/// Estimated number of bytes compaction needs to rewrite to get all levels
+/// under their target size.
+pub const ESTIMATE_PENDING_COMPACTION_BYTES: Property = Property::new(
+    "rocksdb.estimate-pending-compaction-bytes",
+    PropertyType::Int,
+);
+/// Aggregated table properties of the column family, see [`TableProperties`].
+pub const AGGREGATED_TABLE_PROPERTIES: Property =
+    Property::new("rocksdb.aggregated-table-properties", PropertyType::Map);
+/// Current actual delayed write rate, 0 means no delay.
+pub const ACTUAL_DELAYED_WRITE_RATE: Property =
+    Property::new("rocksdb.actual-delayed-write-rate", PropertyType::Int);
+/// 1 if writes have been stopped.
+pub const IS_WRITE_STOPPED: Property = Property::new("rocksdb.is-write-stopped", PropertyType::Int);
+/// Estimated timestamp of the oldest key, only available for FIFO compaction.
+pub const ESTIMATE_OLDEST_KEY_TIME: Property =
+    Property::new("rocksdb.estimate-oldest-key-time", PropertyType::Int);
+/// Capacity of the block cache.
+pub const BLOCK_CACHE_CAPACITY: Property =
+    Property::new("rocksdb.block-cache-capacity", PropertyType::Int);
+/// Memory size of the entries residing in the block cache.
+pub const BLOCK_CACHE_USAGE: Property =
+    Property::new("rocksdb.block-cache-usage", PropertyType::Int);
+/// Memory size of the entries pinned in the block cache.
+pub const BLOCK_CACHE_PINNED_USAGE: Property =
+    Property::new("rocksdb.block-cache-pinned-usage", PropertyType::Int);
+/// The statistics of the database, if enabled in its options.
+pub const OPTIONS_STATISTICS: Property =
+    Property::new("rocksdb.options-statistics", PropertyType::String);
+
+/// Parses a value made of `name=value; ` pairs, such as the aggregated table
+/// properties.
+pub(crate) fn parse_map(value: &str) -> HashMap<String, String> {
+    let mut map: HashMap<String, String> = HashMap::new();
+    let mut last: Option<String> = None;
+    for pair in value.split("; ").filter(|pair| !pair.trim().is_empty()) {
+        let (name, value) = match pair.find('=') {
+            Some(i) => (&pair[..i], &pair[i + 1..]),
+            None => (pair, ""),
+        };
+        // The names of the table properties all contain a space, while the
+        // compression options are themselves `name=value; ` pairs without
+        // spaces, which belong to the value of the previous property.
+        if !name.contains(' ') {
+            if let Some(value) = last.as_ref().and_then(|last| map.get_mut(last)) {
+                value.push_str("; ");
+                value.push_str(pair);
+                continue;
+            }
+        }
+        map.insert(name.to_owned(), value.to_owned());
+        last = Some(name.to_owned());
+    }
+    map
+}
+
+fn parse_number<T: FromStr>(value: &str, what: &str) -> Result<T, Error>
+where
+    T::Err: fmt::Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|e| Error::new(format!("Failed to parse {} '{}': {}", what, value, e)))
+}
+
+/// The number of files and size of one level, as reported by [`LEVELSTATS`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelStat {
+    pub level: usize,
+    pub files: u64,
+    /// Total size of the files in MiB, rounded to the nearest integer.
+    pub size_mb: f64,
+}
+
+/// The value of the [`LEVELSTATS`] property.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelStats {
+    pub levels: Vec<LevelStat>,
+}
+
+impl FromStr for LevelStats {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Error> {
+        let mut levels = Vec::new();
+        // The first two lines are the header and a line of dashes.
+        for line in value.lines().skip(2).filter(|line| !line.trim().is_empty()) {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            if columns.len() != 3 {
+                return Err(Error::new(format!("Invalid level stats line '{}'", line)));
+            }
+            levels.push(LevelStat {
+                level: parse_number(columns[0], "level")?,
+                files: parse_number(columns[1], "number of files")?,
+                size_mb: parse_number(columns[2], "level size")?,
+            });
+        }
+        Ok(Self { levels })
+    }
+}
+
+/// The compaction stats of one level, as reported by [`CFSTATS`].
+///
+/// Sizes and key counts are printed in human readable form by RocksDB, so
+/// they are only approximations of the actual values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompactionStats {
+    /// `L<n>` for a level, `Sum` for the total over all levels and `Int` for
+    /// the total since the stats were last retrieved.
+    pub level: String,
+    pub files: u64,
+    pub compacting_files: u64,
+    pub size_bytes: u64,
+    pub score: f64,
+    pub read_gb: f64,
+    pub rn_gb: f64,
+    pub rnp1_gb: f64,
+    pub write_gb: f64,
+    pub wnew_gb: f64,
+    pub moved_gb: f64,
+    pub write_amp: f64,
+    pub read_mb_per_sec: f64,
+    pub write_mb_per_sec: f64,
+    pub comp_sec: f64,
+    pub comp_merge_cpu_sec: f64,
+    pub comp_count: u64,
+    pub avg_sec: f64,
+    pub key_in: u64,
+    pub key_drop: u64,
+    pub read_blob_gb: f64,
+    pub write_blob_gb: f64,
+}
+
+impl CompactionStats {
+    fn parse(line: &str) -> Result<Self, Error> {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        // The size is printed as a number and a unit, e.g. `1.50 MB`.
+        if columns.len() != 22 {
+            return Err(Error::new(format!(
+                "Invalid compaction stats line '{}'",
+                line
+            )));
+        }
+        let (files, compacting_files) = match columns[1].find('/') {
+            Some(i) => (&columns[1][..i], &columns[1][i + 1..]),
+            None => (columns[1], "0"),
+        };
+        Ok(Self {
+            level: columns[0].to_owned(),
+            files: parse_number(files, "number of files")?,
+            compacting_files: parse_number(compacting_files, "number of files")?,
+            size_bytes: parse_bytes(columns[2], columns[3])?,
+            score: parse_number(columns[4], "score")?,
+            read_gb: parse_number(columns[5], "Read(GB)")?,
+            rn_gb: parse_number(columns[6], "Rn(GB)")?,
+            rnp1_gb: parse_number(columns[7], "Rnp1(GB)")?,
+            write_gb: parse_number(columns[8], "Write(GB)")?,
+            wnew_gb: parse_number(columns[9], "Wnew(GB)")?,
+            moved_gb: parse_number(columns[10], "Moved(GB)")?,
+            write_amp: parse_number(columns[11], "W-Amp")?,
+            read_mb_per_sec: parse_number(columns[12], "Rd(MB/s)")?,
+            write_mb_per_sec: parse_number(columns[13], "Wr(MB/s)")?,
+            comp_sec: parse_number(columns[14], "Comp(sec)")?,
+            comp_merge_cpu_sec: parse_number(columns[15], "CompMergeCPU(sec)")?,
+            comp_count: parse_number(columns[16], "Comp(cnt)")?,
+            avg_sec: parse_number(columns[17], "Avg(sec)")?,
+            key_in: parse_count(columns[18])?,
+            key_drop: parse_count(columns[19])?,
+            read_blob_gb: parse_number(columns[20], "Rblob(GB)")?,
+            write_blob_gb: parse_number(columns[21], "Wblob(GB)")?,
+        })
+    }
+}
+
+fn parse_bytes(size: &str, unit: &str) -> Result<u64, Error> {
+    let exponent = match unit {
+        "KB" => 1,
+        "MB" => 2,
+        "GB" => 3,
+        "TB" => 4,
+        _ => return Err(Error::new(format!("Invalid size unit '{}'", unit))),
+    };
+    let size: f64 = parse_number(size, "size")?;
+    Ok((size * 1024f64.powi(exponent)) as u64)
+}
+
+fn parse_count(value: &str) -> Result<u64, Error> {
+    let (number, multiplier) = match value.as_bytes().last() {
+        Some(b'K') => (&value[..value.len() - 1], 1_000),
+        Some(b'M') => (&value[..value.len() - 1], 1_000_000),
+        Some(b'G') => (&value[..value.len() - 1], 1_000_000_000),
+        _ => (value, 1),
+    };
+    Ok(parse_number::<u64>(number, "key count")? * multiplier)
+}
+
+/// The value of the [`CFSTATS`], [`CFSTATS_NO_FILE_HISTOGRAM`] or [`STATS`]
+/// properties.
+///
+/// Only the per level compaction stats are parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CfStats {
+    pub levels: Vec<CompactionStats>,
+}
+
+impl CfStats {
+    /// Returns the stats of the level named `level`, e.g. `L0` or `Sum`.
+    pub fn level(&self, level: &str) -> Option<&CompactionStats> {
+        self.levels.iter().find(|stats| stats.level == level)
+    }
+}
+
+impl FromStr for CfStats {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Error> {
+        let mut lines = value.lines();
+        // The compaction stats table starts with a header whose first column
+        // is `Level`; it's followed by a table grouped by `Priority` instead.
+        lines
+            .find(|line| line.starts_with("Level ") && line.contains("Files"))
+            .ok_or_else(|| Error::new("Missing compaction stats".to_owned()))?;
+        let levels = lines
+            .skip(1)
+            .take_while(|line| !line.trim().is_empty())
+            .map(CompactionStats::parse)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { levels })
+    }
+}
+
+/// The value of the [`AGGREGATED_TABLE_PROPERTIES`] property, or of
+/// [`aggregated_table_properties_at_level`].
+///
+/// Only the properties that make sense once aggregated are included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableProperties {
+    pub num_data_blocks: u64,
+    pub num_entries: u64,
+    pub num_deletions: u64,
+    pub num_merge_operands: u64,
+    pub num_range_deletions: u64,
+    pub raw_key_size: u64,
+    pub raw_value_size: u64,
+    pub data_size: u64,
+    pub index_size: u64,
+    pub filter_size: u64,
+}
+
+impl FromStr for TableProperties {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Error> {
+        let map = parse_map(value);
+        let get = |name: &str| -> Result<u64, Error> {
+            // The name of the index size includes the index options, e.g.
+            // `index block size (user-key? 1, delta-value? 1)`.
+            let value = map
+                .iter()
+                .find(|(key, _)| key.as_str() == name || key.starts_with(&format!("{} (", name)))
+                .map(|(_, value)| value)
+                .ok_or_else(|| Error::new(format!("Missing table property '{}'", name)))?;
+            parse_number(value, name)
+        };
+        Ok(Self {
+            num_data_blocks: get("# data blocks")?,
+            num_entries: get("# entries")?,
+            num_deletions: get("# deletions")?,
+            num_merge_operands: get("# merge operands")?,
+            num_range_deletions: get("# range deletions")?,
+            raw_key_size: get("raw key size")?,
+            raw_value_size: get("raw value size")?,
+            data_size: get("data block size")?,
+            index_size: get("index block size")?,
+            filter_size: get("filter block size")?,
+        })
+    }
+}
@@ -0,0 +1,360 @@
+// Copyright 2021 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Secondary instances which keep up with their primary automatically.
+//!
+//! A [`ManagedSecondaryWithThreadMode`] opens the database as a secondary and
+//! calls `try_catch_up_with_primary` on a configurable interval from a
+//! background thread. Column families created or dropped by the primary are
+//! discovered along the way; since a secondary instance can't open new column
+//! families in place, the underlying DB is reopened whenever the set changes.
+//!
+//! ```
+//! use rocksdb::{secondary::{CatchUpOptions, ManagedSecondary}, DBRead, Options, DB};
+//! use std::time::Duration;
+//!
+//! let primary_path = "_path_for_rocksdb_storage_managed_primary";
+//! let secondary_path = "_path_for_rocksdb_storage_managed_secondary";
+//! {
+//!     let primary = DB::open_default(primary_path).unwrap();
+//!     primary.put(b"key", b"value").unwrap();
+//!
+//!     let mut opts = Options::default();
+//!     opts.set_max_open_files(-1);
+//!     let mut catch_up_opts = CatchUpOptions::default();
+//!     catch_up_opts.set_interval(Duration::from_millis(100));
+//!     let secondary =
+//!         ManagedSecondary::open(&opts, &catch_up_opts, primary_path, secondary_path).unwrap();
+//!     secondary.subscribe(|event| println!("caught up to {}", event.sequence_number));
+//!     assert!(secondary.db().get(b"key").unwrap().is_some());
+//! }
+//! let _ = DB::destroy(&Options::default(), primary_path);
+//! let _ = DB::destroy(&Options::default(), secondary_path);
+//! ```
+
+use crate::{
+    db::{DBWithThreadMode, ThreadMode},
+    ColumnFamilyDescriptor, DBRead, Error, Options, SecondaryDBWithThreadMode,
+};
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// A type alias to the managed secondary with the thread mode of [`DB`](crate::DB).
+#[cfg(not(feature = "multi-threaded-cf"))]
+pub type ManagedSecondary = ManagedSecondaryWithThreadMode<crate::SingleThreaded>;
+
+#[cfg(feature = "multi-threaded-cf")]
+pub type ManagedSecondary = ManagedSecondaryWithThreadMode<crate::MultiThreaded>;
+
+/// Controls how a [`ManagedSecondaryWithThreadMode`] follows its primary.
+pub struct CatchUpOptions {
+    interval: Duration,
+    discover_column_families: bool,
+}
+
+impl CatchUpOptions {
+    /// Sets the time between two catch-ups of the background thread.
+    ///
+    /// Default: 1 second
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// If true, the column families of the primary are listed before every
+    /// catch-up and the secondary is reopened when they changed.
+    ///
+    /// Default: true
+    pub fn set_discover_column_families(&mut self, discover: bool) {
+        self.discover_column_families = discover;
+    }
+}
+
+impl Default for CatchUpOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(1),
+            discover_column_families: true,
+        }
+    }
+}
+
+/// Describes a completed catch-up, passed to subscribers of a
+/// [`ManagedSecondaryWithThreadMode`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatchUpEvent {
+    /// Latest sequence number visible to the secondary after the catch-up
+    pub sequence_number: u64,
+    /// Column families the primary created since the previous catch-up
+    pub created_column_families: Vec<String>,
+    /// Column families the primary dropped since the previous catch-up
+    pub dropped_column_families: Vec<String>,
+}
+
+type Subscriber = Arc<dyn Fn(&CatchUpEvent) + Send + Sync>;
+
+struct CatchUpState {
+    sequence_number: u64,
+    caught_up_at: Instant,
+    column_families: Vec<String>,
+    last_error: Option<Error>,
+}
+
+struct Shared<T: ThreadMode> {
+    db: RwLock<Arc<SecondaryDBWithThreadMode<T>>>,
+    opts: Options,
+    primary_path: PathBuf,
+    secondary_path: PathBuf,
+    discover_column_families: bool,
+    // Serializes background and manual catch-ups.
+    catch_up_lock: Mutex<()>,
+    state: Mutex<CatchUpState>,
+    subscribers: Mutex<Vec<Subscriber>>,
+    stopped: Mutex<bool>,
+    wakeup: Condvar,
+}
+
+/// A secondary instance that catches up with its primary in a background thread.
+///
+/// Readers obtain the current DB with [`db`](Self::db). The returned `Arc`
+/// stays valid after the instance was reopened to pick up column family
+/// changes, but only sees the column families it was opened with, and no
+/// longer catches up with the primary.
+///
+/// Reopening opens a new secondary instance on the same `secondary_path`
+/// while `Arc`s of the previous one may still be held, so both write their
+/// info log there until the old one is dropped. Drop the DBs obtained from
+/// [`db`](Self::db) promptly, e.g. by calling it for every read, to keep that
+/// window short.
+pub struct ManagedSecondaryWithThreadMode<T: ThreadMode + 'static> {
+    shared: Arc<Shared<T>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<T: ThreadMode + 'static> ManagedSecondaryWithThreadMode<T> {
+    /// Opens the database at `primary_path` as a secondary and starts the
+    /// background catch-up thread.
+    ///
+    /// All column families of the primary are opened, each with a copy of
+    /// `opts`, so merge operators and comparators set there apply to all of them.
+    pub fn open<P: AsRef<Path>>(
+        opts: &Options,
+        catch_up_opts: &CatchUpOptions,
+        primary_path: P,
+        secondary_path: P,
+    ) -> Result<Self, Error> {
+        let primary_path = primary_path.as_ref().to_path_buf();
+        let secondary_path = secondary_path.as_ref().to_path_buf();
+        let column_families = DBWithThreadMode::<T>::list_cf(opts, &primary_path)?;
+        let db = open_secondary(opts, &primary_path, &secondary_path, &column_families)?;
+
+        let shared = Arc::new(Shared {
+            state: Mutex::new(CatchUpState {
+                sequence_number: db.latest_sequence_number(),
+                caught_up_at: Instant::now(),
+                column_families,
+                last_error: None,
+            }),
+            db: RwLock::new(Arc::new(db)),
+            opts: opts.clone(),
+            primary_path,
+            secondary_path,
+            discover_column_families: catch_up_opts.discover_column_families,
+            catch_up_lock: Mutex::new(()),
+            subscribers: Mutex::new(Vec::new()),
+            stopped: Mutex::new(false),
+            wakeup: Condvar::new(),
+        });
+
+        let worker = {
+            let shared = shared.clone();
+            let interval = catch_up_opts.interval;
+            thread::Builder::new()
+                .name("rocksdb-catch-up".to_owned())
+                .spawn(move || shared.run(interval))
+                .map_err(|e| Error::new(format!("Failed to spawn catch-up thread: {}", e)))?
+        };
+
+        Ok(Self {
+            shared,
+            worker: Some(worker),
+        })
+    }
+
+    /// Returns the current secondary DB.
+    pub fn db(&self) -> Arc<SecondaryDBWithThreadMode<T>> {
+        self.shared.db.read().unwrap().clone()
+    }
+
+    /// Catches up with the primary immediately instead of waiting for the
+    /// background thread.
+    pub fn catch_up(&self) -> Result<CatchUpEvent, Error> {
+        self.shared.catch_up()
+    }
+
+    /// Returns the latest sequence number seen by the last successful catch-up.
+    pub fn last_sequence_number(&self) -> u64 {
+        self.shared.state.lock().unwrap().sequence_number
+    }
+
+    /// Returns the time elapsed since the last successful catch-up.
+    ///
+    /// This is not how far the secondary is behind the primary: writes made
+    /// since then are only known to the primary. Compare
+    /// [`last_sequence_number`](Self::last_sequence_number) with the primary's
+    /// `latest_sequence_number` for that.
+    pub fn since_last_catch_up(&self) -> Duration {
+        self.shared.state.lock().unwrap().caught_up_at.elapsed()
+    }
+
+    /// Returns the error of the last catch-up if it failed.
+    pub fn last_error(&self) -> Option<Error> {
+        self.shared.state.lock().unwrap().last_error.clone()
+    }
+
+    /// Returns the names of the column families currently open.
+    pub fn column_families(&self) -> Vec<String> {
+        self.shared.state.lock().unwrap().column_families.clone()
+    }
+
+    /// Registers a callback invoked after each successful catch-up.
+    ///
+    /// Callbacks run on the thread performing the catch-up, after it released
+    /// its locks, so they may call back into the handle, e.g. to catch up again
+    /// or to subscribe further callbacks.
+    pub fn subscribe<F>(&self, callback: F)
+    where
+        F: Fn(&CatchUpEvent) + Send + Sync + 'static,
+    {
+        self.shared
+            .subscribers
+            .lock()
+            .unwrap()
+            .push(Arc::new(callback));
+    }
+}
+
+impl<T: ThreadMode + 'static> Drop for ManagedSecondaryWithThreadMode<T> {
+    fn drop(&mut self) {
+        *self.shared.stopped.lock().unwrap() = true;
+        self.shared.wakeup.notify_all();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl<T: ThreadMode> Shared<T> {
+    fn run(&self, interval: Duration) {
+        loop {
+            {
+                let stopped = self.stopped.lock().unwrap();
+                let (stopped, _) = self
+                    .wakeup
+                    .wait_timeout_while(stopped, interval, |stopped| !*stopped)
+                    .unwrap();
+                if *stopped {
+                    return;
+                }
+            }
+            // Failures are recorded in the state and retried on the next tick.
+            let _ = self.catch_up();
+        }
+    }
+
+    fn catch_up(&self) -> Result<CatchUpEvent, Error> {
+        let guard = self.catch_up_lock.lock().unwrap();
+        let result = self.catch_up_locked();
+        let mut state = self.state.lock().unwrap();
+        match &result {
+            Ok(event) => {
+                state.sequence_number = event.sequence_number;
+                state.caught_up_at = Instant::now();
+                state.last_error = None;
+            }
+            Err(e) => state.last_error = Some(e.clone()),
+        }
+        drop(state);
+        drop(guard);
+
+        if let Ok(event) = &result {
+            // Callbacks run without any lock held so they can use the handle.
+            let subscribers = self.subscribers.lock().unwrap().clone();
+            for subscriber in &subscribers {
+                subscriber(event);
+            }
+        }
+        result
+    }
+
+    fn catch_up_locked(&self) -> Result<CatchUpEvent, Error> {
+        let mut created_column_families = Vec::new();
+        let mut dropped_column_families = Vec::new();
+
+        if self.discover_column_families {
+            let column_families = DBWithThreadMode::<T>::list_cf(&self.opts, &self.primary_path)?;
+            let current = self.state.lock().unwrap().column_families.clone();
+            created_column_families = column_families
+                .iter()
+                .filter(|name| !current.contains(name))
+                .cloned()
+                .collect();
+            dropped_column_families = current
+                .iter()
+                .filter(|name| !column_families.contains(name))
+                .cloned()
+                .collect();
+
+            if !created_column_families.is_empty() || !dropped_column_families.is_empty() {
+                // A freshly opened secondary is already caught up.
+                let db = open_secondary(
+                    &self.opts,
+                    &self.primary_path,
+                    &self.secondary_path,
+                    &column_families,
+                )?;
+                let sequence_number = db.latest_sequence_number();
+                *self.db.write().unwrap() = Arc::new(db);
+                self.state.lock().unwrap().column_families = column_families;
+                return Ok(CatchUpEvent {
+                    sequence_number,
+                    created_column_families,
+                    dropped_column_families,
+                });
+            }
+        }
+
+        let db = self.db.read().unwrap().clone();
+        db.try_catch_up_with_primary()?;
+        Ok(CatchUpEvent {
+            sequence_number: db.latest_sequence_number(),
+            created_column_families,
+            dropped_column_families,
+        })
+    }
+}
+
+fn open_secondary<T: ThreadMode>(
+    opts: &Options,
+    primary_path: &Path,
+    secondary_path: &Path,
+    column_families: &[String],
+) -> Result<SecondaryDBWithThreadMode<T>, Error> {
+    let cfs = column_families
+        .iter()
+        .map(|name| ColumnFamilyDescriptor::new(name.as_str(), opts.clone()));
+    SecondaryDBWithThreadMode::open_cf_descriptors(opts, primary_path, secondary_path, cfs)
+}
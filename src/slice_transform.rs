@@ -25,6 +25,7 @@ use crate::ffi;
 /// ColumnFamilyOptions.
 pub struct SliceTransform {
     pub inner: *mut ffi::rocksdb_slicetransform_t,
+    pub(crate) name: String,
 }
 
 // NB we intentionally don't implement a Drop that passes
@@ -57,18 +58,23 @@ impl SliceTransform {
             )
         };
 
-        SliceTransform { inner: st }
+        SliceTransform {
+            inner: st,
+            name: name.to_owned(),
+        }
     }
 
     pub fn create_fixed_prefix(len: size_t) -> SliceTransform {
         SliceTransform {
             inner: unsafe { ffi::rocksdb_slicetransform_create_fixed_prefix(len) },
+            name: format!("rocksdb.FixedPrefix.{}", len),
         }
     }
 
     pub fn create_noop() -> SliceTransform {
         SliceTransform {
             inner: unsafe { ffi::rocksdb_slicetransform_create_noop() },
+            name: "rocksdb.Noop".to_owned(),
         }
     }
 }
@@ -14,9 +14,12 @@
 
 use crate::{
     db::DBAccess, ffi, AsColumnFamilyRef, DBIteratorWithThreadMode, DBRawIteratorWithThreadMode,
-    Error, IteratorMode, ReadOptions, DB,
+    DBWithThreadMode, Error, IteratorMode, OwnedIterator, OwnedRawIterator, ReadOptions,
+    ThreadMode, DB,
 };
 
+use std::sync::Arc;
+
 /// A type alias to keep compatibility. See [`SnapshotWithThreadMode`] for details
 pub type Snapshot<'a> = SnapshotWithThreadMode<'a, DB>;
 
@@ -174,3 +177,184 @@ impl<'a, D: DBAccess> Drop for SnapshotWithThreadMode<'a, D> {
 /// immutable and can be safely shared between threads.
 unsafe impl<'a, D: DBAccess> Send for SnapshotWithThreadMode<'a, D> {}
 unsafe impl<'a, D: DBAccess> Sync for SnapshotWithThreadMode<'a, D> {}
+
+/// A consistent view of the database at the point of creation, which owns a
+/// reference to the database instead of borrowing it.
+///
+/// Unlike [`SnapshotWithThreadMode`], an `OwnedSnapshot` is `'static`, so it
+/// can be stored in long-lived structs or moved to another thread or task.
+/// Cloning it is cheap and shares the underlying snapshot, which is released
+/// when the last clone and the last iterator created from it are dropped.
+///
+/// # Examples
+///
+/// ```
+/// use rocksdb::{DB, IteratorMode, Options, OwnedSnapshot};
+/// use std::sync::Arc;
+///
+/// let path = "_path_for_rocksdb_storage_owned_snapshot";
+/// {
+///     let db = Arc::new(DB::open_default(path).unwrap());
+///     db.put(b"k1", b"v1").unwrap();
+///     let snapshot = OwnedSnapshot::new(db.clone());
+///     db.put(b"k1", b"v2").unwrap();
+///
+///     std::thread::spawn(move || {
+///         assert_eq!(snapshot.get(b"k1").unwrap().unwrap(), b"v1");
+///         assert_eq!(snapshot.iterator(IteratorMode::Start).count(), 1);
+///     })
+///     .join()
+///     .unwrap();
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+pub struct OwnedSnapshot<T: ThreadMode + 'static> {
+    inner: Arc<OwnedSnapshotInner<T>>,
+}
+
+struct OwnedSnapshotInner<T: ThreadMode + 'static> {
+    // Declared before `db` so that the snapshot is released before the
+    // reference to the database is dropped.
+    snapshot: SnapshotWithThreadMode<'static, DBWithThreadMode<T>>,
+    db: Arc<DBWithThreadMode<T>>,
+}
+
+impl<T: ThreadMode + 'static> OwnedSnapshot<T> {
+    /// Creates a new `OwnedSnapshot` of the database `db`.
+    pub fn new(db: Arc<DBWithThreadMode<T>>) -> Self {
+        // SAFETY: the database is kept alive by `db` for as long as the
+        // snapshot exists, see `OwnedSnapshotInner`.
+        let db_ref: &'static DBWithThreadMode<T> = unsafe { &*Arc::as_ptr(&db) };
+        Self {
+            inner: Arc::new(OwnedSnapshotInner {
+                snapshot: SnapshotWithThreadMode::new(db_ref),
+                db,
+            }),
+        }
+    }
+
+    /// Returns the database this snapshot belongs to.
+    pub fn db(&self) -> &Arc<DBWithThreadMode<T>> {
+        &self.inner.db
+    }
+
+    /// Creates an iterator over the data in this snapshot, using the default read options.
+    pub fn iterator(&self, mode: IteratorMode) -> OwnedIterator<T> {
+        self.iterator_opt(mode, ReadOptions::default())
+    }
+
+    /// Creates an iterator over the data in this snapshot under the given column family, using
+    /// the default read options.
+    pub fn iterator_cf(
+        &self,
+        cf_handle: &impl AsColumnFamilyRef,
+        mode: IteratorMode,
+    ) -> OwnedIterator<T> {
+        self.iterator_cf_opt(cf_handle, ReadOptions::default(), mode)
+    }
+
+    /// Creates an iterator over the data in this snapshot, using the given read options.
+    pub fn iterator_opt(&self, mode: IteratorMode, mut readopts: ReadOptions) -> OwnedIterator<T> {
+        readopts.set_snapshot(&self.inner.snapshot);
+        OwnedIterator::build(
+            self.inner.db.clone(),
+            None,
+            readopts,
+            mode,
+            Some(self.clone()),
+        )
+    }
+
+    /// Creates an iterator over the data in this snapshot under the given column family, using
+    /// the given read options.
+    pub fn iterator_cf_opt(
+        &self,
+        cf_handle: &impl AsColumnFamilyRef,
+        mut readopts: ReadOptions,
+        mode: IteratorMode,
+    ) -> OwnedIterator<T> {
+        readopts.set_snapshot(&self.inner.snapshot);
+        OwnedIterator::build(
+            self.inner.db.clone(),
+            Some(cf_handle.inner()),
+            readopts,
+            mode,
+            Some(self.clone()),
+        )
+    }
+
+    /// Creates a raw iterator over the data in this snapshot, using the default read options.
+    pub fn raw_iterator(&self) -> OwnedRawIterator<T> {
+        self.raw_iterator_opt(ReadOptions::default())
+    }
+
+    /// Creates a raw iterator over the data in this snapshot under the given column family, using
+    /// the default read options.
+    pub fn raw_iterator_cf(&self, cf_handle: &impl AsColumnFamilyRef) -> OwnedRawIterator<T> {
+        self.raw_iterator_cf_opt(cf_handle, ReadOptions::default())
+    }
+
+    /// Creates a raw iterator over the data in this snapshot, using the given read options.
+    pub fn raw_iterator_opt(&self, mut readopts: ReadOptions) -> OwnedRawIterator<T> {
+        readopts.set_snapshot(&self.inner.snapshot);
+        OwnedRawIterator::build(self.inner.db.clone(), None, readopts, Some(self.clone()))
+    }
+
+    /// Creates a raw iterator over the data in this snapshot under the given column family, using
+    /// the given read options.
+    pub fn raw_iterator_cf_opt(
+        &self,
+        cf_handle: &impl AsColumnFamilyRef,
+        mut readopts: ReadOptions,
+    ) -> OwnedRawIterator<T> {
+        readopts.set_snapshot(&self.inner.snapshot);
+        OwnedRawIterator::build(
+            self.inner.db.clone(),
+            Some(cf_handle.inner()),
+            readopts,
+            Some(self.clone()),
+        )
+    }
+
+    /// Returns the bytes associated with a key value with default read options.
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<Vec<u8>>, Error> {
+        self.inner.snapshot.get(key)
+    }
+
+    /// Returns the bytes associated with a key value and given column family with default read
+    /// options.
+    pub fn get_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        self.inner.snapshot.get_cf(cf, key)
+    }
+
+    /// Returns the bytes associated with a key value and given read options.
+    pub fn get_opt<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        readopts: ReadOptions,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        self.inner.snapshot.get_opt(key, readopts)
+    }
+
+    /// Returns the bytes associated with a key value, given column family and read options.
+    pub fn get_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        readopts: ReadOptions,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        self.inner.snapshot.get_cf_opt(cf, key, readopts)
+    }
+}
+
+impl<T: ThreadMode + 'static> Clone for OwnedSnapshot<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
@@ -16,7 +16,11 @@
 use crate::{ffi, ffi_util::to_cpath, Error, Options};
 
 use libc::{self, c_char, size_t};
-use std::{ffi::CString, marker::PhantomData, path::Path};
+use std::{
+    ffi::CString,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
 
 /// SstFileWriter is used to create sst files that can be added to database later
 /// All keys in files generated by SstFileWriter will have sequence number = 0.
@@ -25,6 +29,28 @@ pub struct SstFileWriter<'a> {
     // Options are needed to be alive when calling open(),
     // so let's make sure it doesn't get, dropped for the lifetime of SstFileWriter
     phantom: PhantomData<&'a Options>,
+    // The C API doesn't expose the file info, so it is tracked while writing.
+    path: Option<PathBuf>,
+    smallest_key: Option<Vec<u8>>,
+    largest_key: Vec<u8>,
+    num_entries: u64,
+}
+
+/// The metadata of a SST file written by [`SstFileWriter`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalSstFileInfo {
+    /// Path of the file
+    pub file_path: PathBuf,
+    /// Smallest user defined key in the file
+    pub smallest_key: Option<Vec<u8>>,
+    /// Largest user defined key in the file
+    pub largest_key: Option<Vec<u8>>,
+    /// Sequence number of all keys in the file, which is always 0
+    pub sequence_number: u64,
+    /// Size of the file
+    pub file_size: u64,
+    /// Number of entries (puts, merges and deletions) in the file
+    pub num_entries: u64,
 }
 
 unsafe impl<'a> Send for SstFileWriter<'a> {}
@@ -51,6 +77,10 @@ impl Default for EnvOptions {
 
 impl<'a> SstFileWriter<'a> {
     /// Initializes SstFileWriter with given DB options.
+    ///
+    /// To ingest the file into a column family, pass the options the column
+    /// family is opened with, so that the comparator, prefix extractor and
+    /// compression of the file match it.
     pub fn create(opts: &'a Options) -> Self {
         let env_options = EnvOptions::default();
 
@@ -59,6 +89,10 @@ impl<'a> SstFileWriter<'a> {
         Self {
             inner: writer,
             phantom: PhantomData,
+            path: None,
+            smallest_key: None,
+            largest_key: Vec::new(),
+            num_entries: 0,
         }
     }
 
@@ -67,12 +101,17 @@ impl<'a> SstFileWriter<'a> {
     }
 
     /// Prepare SstFileWriter to write into file located at "file_path".
-    pub fn open<P: AsRef<Path>>(&'a self, path: P) -> Result<(), Error> {
+    pub fn open<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
         let cpath = to_cpath(&path)?;
-        self.open_raw(&cpath)
+        self.open_raw(&cpath)?;
+        self.path = Some(path.as_ref().to_path_buf());
+        self.smallest_key = None;
+        self.largest_key.clear();
+        self.num_entries = 0;
+        Ok(())
     }
 
-    fn open_raw(&'a self, cpath: &CString) -> Result<(), Error> {
+    fn open_raw(&self, cpath: &CString) -> Result<(), Error> {
         unsafe {
             ffi_try!(ffi::rocksdb_sstfilewriter_open(
                 self.inner,
@@ -84,11 +123,22 @@ impl<'a> SstFileWriter<'a> {
     }
 
     /// Finalize writing to sst file and close file.
-    pub fn finish(&mut self) -> Result<(), Error> {
+    ///
+    /// Returns the metadata of the written file.
+    pub fn finish(&mut self) -> Result<ExternalSstFileInfo, Error> {
         unsafe {
             ffi_try!(ffi::rocksdb_sstfilewriter_finish(self.inner,));
-            Ok(())
         }
+        let smallest_key = self.smallest_key.take();
+        let largest_key = std::mem::take(&mut self.largest_key);
+        Ok(ExternalSstFileInfo {
+            file_path: self.path.take().unwrap_or_default(),
+            largest_key: smallest_key.as_ref().map(|_| largest_key),
+            smallest_key,
+            sequence_number: 0,
+            file_size: self.file_size(),
+            num_entries: std::mem::take(&mut self.num_entries),
+        })
     }
 
     /// returns the current file size
@@ -118,8 +168,9 @@ impl<'a> SstFileWriter<'a> {
                 value.as_ptr() as *const c_char,
                 value.len() as size_t,
             ));
-            Ok(())
         }
+        self.record_key(key);
+        Ok(())
     }
 
     /// Adds a Merge key with value to currently opened file
@@ -140,8 +191,9 @@ impl<'a> SstFileWriter<'a> {
                 value.as_ptr() as *const c_char,
                 value.len() as size_t,
             ));
-            Ok(())
         }
+        self.record_key(key);
+        Ok(())
     }
 
     /// Adds a deletion key to currently opened file
@@ -155,8 +207,18 @@ impl<'a> SstFileWriter<'a> {
                 key.as_ptr() as *const c_char,
                 key.len() as size_t,
             ));
-            Ok(())
         }
+        self.record_key(key);
+        Ok(())
+    }
+
+    fn record_key(&mut self, key: &[u8]) {
+        if self.smallest_key.is_none() {
+            self.smallest_key = Some(key.to_vec());
+        }
+        self.largest_key.clear();
+        self.largest_key.extend_from_slice(key);
+        self.num_entries += 1;
     }
 }
 
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{ffi, AsColumnFamilyRef};
+use crate::{ffi, AsColumnFamilyRef, Error};
 use libc::{c_char, c_void, size_t};
 use std::slice;
 
@@ -49,6 +49,32 @@ pub trait WriteBatchIterator {
     fn delete(&mut self, key: Box<[u8]>);
 }
 
+/// Receives every operation of a write batch together with the ID of the
+/// column family it targets.
+///
+/// Unlike [`WriteBatchIterator`], this also reports merges, single deletes,
+/// range deletions and log data blobs, which makes it suitable for replaying
+/// batches read from a [`DBWALIterator`](crate::DBWALIterator). Column family
+/// IDs can be resolved to names with `DBWithThreadMode::cf_name_by_id`.
+pub trait WriteBatchIteratorCf {
+    /// Called with a key and value that were `put` into the batch.
+    fn put_cf(&mut self, cf_id: u32, key: &[u8], value: &[u8]);
+    /// Called with a key and value that were `merge`d into the batch.
+    fn merge_cf(&mut self, cf_id: u32, key: &[u8], value: &[u8]);
+    /// Called with a key that was `delete`d from the batch.
+    fn delete_cf(&mut self, cf_id: u32, key: &[u8]);
+    /// Called with a key that was `single_delete`d from the batch.
+    fn single_delete_cf(&mut self, cf_id: u32, key: &[u8]);
+    /// Called with the bounds of a range `["from", "to")` deleted from the batch.
+    fn delete_range_cf(&mut self, cf_id: u32, from: &[u8], to: &[u8]);
+    /// Called with a blob that was added with `put_log_data`. Does nothing by default.
+    fn log_data(&mut self, _blob: &[u8]) {}
+    /// Called with a key and the encoded reference to a value stored in a blob file. RocksDB
+    /// writes these records itself, e.g. when BlobDB moves values out of the LSM tree. Does
+    /// nothing by default.
+    fn put_blob_index_cf(&mut self, _cf_id: u32, _key: &[u8], _blob_index: &[u8]) {}
+}
+
 unsafe extern "C" fn writebatch_put_callback(
     state: *mut c_void,
     k: *const c_char,
@@ -114,6 +140,54 @@ impl WriteBatch {
         }
     }
 
+    /// Iterate all operations within this write batch, including the column
+    /// family each of them targets. The operations are reported in the order
+    /// they were added to the batch by invoking the matching member function
+    /// of the provided `WriteBatchIteratorCf` trait implementation.
+    ///
+    /// Returns an error if the batch contains an entry that can't be decoded.
+    pub fn iterate_cf(&self, callbacks: &mut dyn WriteBatchIteratorCf) -> Result<(), Error> {
+        for_each_record(self.data(), |record| match record {
+            Record::Put { cf_id, key, value } => callbacks.put_cf(cf_id, key, value),
+            Record::Merge { cf_id, key, value } => callbacks.merge_cf(cf_id, key, value),
+            Record::Delete { cf_id, key } => callbacks.delete_cf(cf_id, key),
+            Record::SingleDelete { cf_id, key } => callbacks.single_delete_cf(cf_id, key),
+            Record::DeleteRange { cf_id, from, to } => callbacks.delete_range_cf(cf_id, from, to),
+            Record::LogData(blob) => callbacks.log_data(blob),
+            Record::PutBlobIndex {
+                cf_id,
+                key,
+                blob_index,
+            } => callbacks.put_blob_index_cf(cf_id, key, blob_index),
+            Record::Marker => {}
+        })
+    }
+
+    /// Return the serialized representation of this write batch.
+    pub fn data(&self) -> &[u8] {
+        unsafe {
+            let mut batch_size: size_t = 0;
+            let batch_data = ffi::rocksdb_writebatch_data(self.inner, &mut batch_size);
+            slice::from_raw_parts(batch_data as *const u8, batch_size as usize)
+        }
+    }
+
+    /// Returns the ID of the given column family.
+    ///
+    /// The C API offers no direct accessor, so the ID is read back from a
+    /// scratch batch holding one delete for that column family.
+    pub(crate) fn column_family_id(cf: &impl AsColumnFamilyRef) -> Result<u32, Error> {
+        let mut batch = Self::default();
+        batch.delete_cf(cf, b"");
+        let mut id = None;
+        for_each_record(batch.data(), |record| {
+            if let Record::Delete { cf_id, .. } = record {
+                id = Some(cf_id);
+            }
+        })?;
+        id.ok_or_else(|| Error::new("Could not determine column family ID".to_owned()))
+    }
+
     /// Insert a value into the database under the given key.
     pub fn put<K, V>(&mut self, key: K, value: V)
     where
@@ -219,6 +293,50 @@ impl WriteBatch {
         }
     }
 
+    /// Removes the database entry for key that was written exactly once with
+    /// `put` and never overwritten. Does nothing if the key was not found.
+    pub fn single_delete<K: AsRef<[u8]>>(&mut self, key: K) {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_singledelete(
+                self.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            );
+        }
+    }
+
+    /// Removes the database entry in the given column family for key that was
+    /// written exactly once with `put` and never overwritten. Does nothing if
+    /// the key was not found.
+    pub fn single_delete_cf<K: AsRef<[u8]>>(&mut self, cf: &impl AsColumnFamilyRef, key: K) {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_singledelete_cf(
+                self.inner,
+                cf.inner(),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            );
+        }
+    }
+
+    /// Append a blob of arbitrary size to the records in this batch. The blob
+    /// is stored in the WAL only and is not applied to any column family.
+    pub fn put_log_data<B: AsRef<[u8]>>(&mut self, blob: B) {
+        let blob = blob.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_put_log_data(
+                self.inner,
+                blob.as_ptr() as *const c_char,
+                blob.len() as size_t,
+            );
+        }
+    }
+
     /// Remove database entries from start key to end key.
     ///
     /// Removes the database entries in the range ["begin_key", "end_key"), i.e.,
@@ -283,3 +401,207 @@ impl Drop for WriteBatch {
 }
 
 unsafe impl Send for WriteBatch {}
+
+// Record tags of the serialized write batch, see `db/dbformat.h` in RocksDB.
+const TAG_DELETION: u8 = 0x0;
+const TAG_VALUE: u8 = 0x1;
+const TAG_MERGE: u8 = 0x2;
+const TAG_LOG_DATA: u8 = 0x3;
+const TAG_CF_DELETION: u8 = 0x4;
+const TAG_CF_VALUE: u8 = 0x5;
+const TAG_CF_MERGE: u8 = 0x6;
+const TAG_SINGLE_DELETION: u8 = 0x7;
+const TAG_CF_SINGLE_DELETION: u8 = 0x8;
+const TAG_BEGIN_PREPARE_XID: u8 = 0x9;
+const TAG_END_PREPARE_XID: u8 = 0xA;
+const TAG_COMMIT_XID: u8 = 0xB;
+const TAG_ROLLBACK_XID: u8 = 0xC;
+const TAG_NOOP: u8 = 0xD;
+const TAG_CF_RANGE_DELETION: u8 = 0xE;
+const TAG_RANGE_DELETION: u8 = 0xF;
+const TAG_CF_BLOB_INDEX: u8 = 0x10;
+const TAG_BLOB_INDEX: u8 = 0x11;
+const TAG_BEGIN_PERSISTED_PREPARE_XID: u8 = 0x12;
+const TAG_BEGIN_UNPREPARE_XID: u8 = 0x13;
+
+/// Size of the sequence number and count preceding the records.
+const HEADER_SIZE: usize = 12;
+
+enum Record<'a> {
+    Put {
+        cf_id: u32,
+        key: &'a [u8],
+        value: &'a [u8],
+    },
+    Merge {
+        cf_id: u32,
+        key: &'a [u8],
+        value: &'a [u8],
+    },
+    Delete {
+        cf_id: u32,
+        key: &'a [u8],
+    },
+    SingleDelete {
+        cf_id: u32,
+        key: &'a [u8],
+    },
+    DeleteRange {
+        cf_id: u32,
+        from: &'a [u8],
+        to: &'a [u8],
+    },
+    LogData(&'a [u8]),
+    PutBlobIndex {
+        cf_id: u32,
+        key: &'a [u8],
+        blob_index: &'a [u8],
+    },
+    /// Transaction markers and no-ops, which carry no data.
+    Marker,
+}
+
+struct RecordReader<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> RecordReader<'a> {
+    fn byte(&mut self) -> Option<u8> {
+        let (first, rest) = self.input.split_first()?;
+        self.input = rest;
+        Some(*first)
+    }
+
+    fn varint32(&mut self) -> Option<u32> {
+        let mut result: u32 = 0;
+        for shift in (0..=28).step_by(7) {
+            let byte = self.byte()?;
+            result |= u32::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(result);
+            }
+        }
+        None
+    }
+
+    fn length_prefixed(&mut self) -> Option<&'a [u8]> {
+        let len = self.varint32()? as usize;
+        if self.input.len() < len {
+            return None;
+        }
+        let (slice, rest) = self.input.split_at(len);
+        self.input = rest;
+        Some(slice)
+    }
+
+    /// Decodes the next record, or returns `None` if it is malformed.
+    fn record(&mut self) -> Option<Record<'a>> {
+        let tag = self.byte()?;
+        let cf_id = match tag {
+            TAG_CF_VALUE
+            | TAG_CF_MERGE
+            | TAG_CF_DELETION
+            | TAG_CF_SINGLE_DELETION
+            | TAG_CF_RANGE_DELETION
+            | TAG_CF_BLOB_INDEX => self.varint32()?,
+            _ => 0,
+        };
+        let record = match tag {
+            TAG_VALUE | TAG_CF_VALUE => Record::Put {
+                cf_id,
+                key: self.length_prefixed()?,
+                value: self.length_prefixed()?,
+            },
+            TAG_MERGE | TAG_CF_MERGE => Record::Merge {
+                cf_id,
+                key: self.length_prefixed()?,
+                value: self.length_prefixed()?,
+            },
+            TAG_DELETION | TAG_CF_DELETION => Record::Delete {
+                cf_id,
+                key: self.length_prefixed()?,
+            },
+            TAG_SINGLE_DELETION | TAG_CF_SINGLE_DELETION => Record::SingleDelete {
+                cf_id,
+                key: self.length_prefixed()?,
+            },
+            TAG_RANGE_DELETION | TAG_CF_RANGE_DELETION => Record::DeleteRange {
+                cf_id,
+                from: self.length_prefixed()?,
+                to: self.length_prefixed()?,
+            },
+            TAG_LOG_DATA => Record::LogData(self.length_prefixed()?),
+            TAG_BLOB_INDEX | TAG_CF_BLOB_INDEX => Record::PutBlobIndex {
+                cf_id,
+                key: self.length_prefixed()?,
+                blob_index: self.length_prefixed()?,
+            },
+            TAG_NOOP
+            | TAG_BEGIN_PREPARE_XID
+            | TAG_BEGIN_PERSISTED_PREPARE_XID
+            | TAG_BEGIN_UNPREPARE_XID => Record::Marker,
+            TAG_END_PREPARE_XID | TAG_COMMIT_XID | TAG_ROLLBACK_XID => {
+                self.length_prefixed()?;
+                Record::Marker
+            }
+            _ => return None,
+        };
+        Some(record)
+    }
+}
+
+/// Decodes the serialized write batch `data`, calling `f` for every record.
+fn for_each_record<'a, F>(data: &'a [u8], mut f: F) -> Result<(), Error>
+where
+    F: FnMut(Record<'a>),
+{
+    if data.len() < HEADER_SIZE {
+        return Err(Error::new("Malformed WriteBatch (too small)".to_owned()));
+    }
+    let mut reader = RecordReader {
+        input: &data[HEADER_SIZE..],
+    };
+    while !reader.input.is_empty() {
+        match reader.record() {
+            Some(Record::Marker) => {}
+            Some(record) => f(record),
+            None => return Err(Error::new("Malformed WriteBatch record".to_owned())),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{for_each_record, Record, HEADER_SIZE};
+
+    #[test]
+    fn test_blob_index_records() {
+        let mut data = vec![0; HEADER_SIZE];
+        data.extend_from_slice(&[0x11, 1, b'a', 2, 0xAB, 0xCD]);
+        data.extend_from_slice(&[0x10, 7, 1, b'b', 1, 0xEF]);
+        data.extend_from_slice(&[0x1, 1, b'c', 1, b'd']);
+
+        let mut records = Vec::new();
+        for_each_record(&data, |record| match record {
+            Record::PutBlobIndex {
+                cf_id,
+                key,
+                blob_index,
+            } => records.push((cf_id, key.to_vec(), blob_index.to_vec())),
+            Record::Put { cf_id, key, value } => {
+                records.push((cf_id, key.to_vec(), value.to_vec()))
+            }
+            _ => panic!("unexpected record"),
+        })
+        .unwrap();
+        assert_eq!(
+            records,
+            vec![
+                (0, b"a".to_vec(), vec![0xAB, 0xCD]),
+                (7, b"b".to_vec(), vec![0xEF]),
+                (0, b"c".to_vec(), b"d".to_vec()),
+            ]
+        );
+    }
+}
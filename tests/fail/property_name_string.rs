@@ -0,0 +1,6 @@
+use rocksdb::{DBWithThreadMode, Options, SingleThreaded};
+
+fn main() {
+    let db = DBWithThreadMode::<SingleThreaded>::open(&Options::default(), "foo").unwrap();
+    let _ = db.int_property("rocksdb.estimate-num-key");
+}
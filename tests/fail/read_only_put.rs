@@ -0,0 +1,7 @@
+use rocksdb::{Options, ReadOnlyDBWithThreadMode, SingleThreaded};
+
+fn main() {
+    let db = ReadOnlyDBWithThreadMode::<SingleThreaded>::open(&Options::default(), "foo", false)
+        .unwrap();
+    db.put(b"key", b"value").unwrap();
+}
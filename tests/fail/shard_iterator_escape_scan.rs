@@ -0,0 +1,8 @@
+use rocksdb::{DBWithThreadMode, ParallelScanner, SingleThreaded};
+use std::sync::Arc;
+
+fn main() {
+    let db = Arc::new(DBWithThreadMode::<SingleThreaded>::open_default("foo").unwrap());
+    let scanner = ParallelScanner::new(db, 4);
+    let _iters = scanner.scan(|_, _, iter| iter);
+}
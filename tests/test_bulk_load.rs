@@ -0,0 +1,110 @@
+// Copyright 2021 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod util;
+
+use pretty_assertions::assert_eq;
+
+use rocksdb::{BulkLoadOptions, BulkLoader, DuplicateKeyPolicy, IteratorMode, Options, DB};
+use util::DBPath;
+
+#[test]
+fn test_bulk_load_spills_and_dedupes() {
+    let path = DBPath::new("_rust_rocksdb_test_bulk_load_spills_and_dedupes");
+    let db = DB::open_default(&path).unwrap();
+
+    let mut opts = BulkLoadOptions::default();
+    // Small enough to spill many runs and write several files.
+    opts.set_memory_budget(4096);
+    opts.set_target_file_size(16 * 1024);
+    opts.set_threads(3);
+
+    let mut loader = BulkLoader::new(&Options::default(), &opts).unwrap();
+    // Keys are added in a scrambled order, and every key twice.
+    for round in 0..2u32 {
+        for i in 0..2000u32 {
+            let key = (i * 7919 % 2000).to_be_bytes();
+            loader
+                .put(key, format!("{}-{}", i * 7919 % 2000, round))
+                .unwrap();
+        }
+    }
+    let summary = loader.ingest(&db).unwrap();
+    assert_eq!(summary.keys, 2000);
+    assert_eq!(summary.duplicates, 2000);
+    assert!(summary.files > 1);
+
+    let mut count = 0;
+    for (i, (key, value)) in db.iterator(IteratorMode::Start).enumerate() {
+        assert_eq!(&*key, &(i as u32).to_be_bytes());
+        assert_eq!(&*value, format!("{}-1", i).as_bytes());
+        count += 1;
+    }
+    assert_eq!(count, 2000);
+}
+
+#[test]
+fn test_bulk_load_duplicate_error() {
+    let path = DBPath::new("_rust_rocksdb_test_bulk_load_duplicate_error");
+    let db = DB::open_default(&path).unwrap();
+
+    let mut opts = BulkLoadOptions::default();
+    opts.set_duplicate_key_policy(DuplicateKeyPolicy::Error);
+    opts.set_memory_budget(64);
+
+    // Duplicates within the in-memory buffer.
+    let mut loader = BulkLoader::new(&Options::default(), &opts).unwrap();
+    loader.extend(vec![(b"a", b"1"), (b"a", b"2")]).unwrap();
+    assert!(loader.ingest(&db).is_err());
+
+    // Duplicates across spilled runs.
+    let mut loader = BulkLoader::new(&Options::default(), &opts).unwrap();
+    loader.put(b"key", [0; 100]).unwrap();
+    loader.put(b"other", [0; 100]).unwrap();
+    loader.put(b"key", [1; 100]).unwrap();
+    assert!(loader.ingest(&db).is_err());
+
+    assert!(db.get(b"a").unwrap().is_none());
+    assert!(db.get(b"key").unwrap().is_none());
+}
+
+#[test]
+fn test_bulk_load_column_families() {
+    let path = DBPath::new("_rust_rocksdb_test_bulk_load_column_families");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = DB::open_cf(&opts, &path, &["cf1", "cf2"]).unwrap();
+
+    for (name, value) in &[("cf1", b"one"), ("cf2", b"two")] {
+        let cf = db.cf_handle(name).unwrap();
+        let mut loader = BulkLoader::new(&Options::default(), &BulkLoadOptions::default()).unwrap();
+        loader.put(b"k2", value).unwrap();
+        loader.put(b"k1", value).unwrap();
+        let summary = loader.ingest_cf(&db, &cf).unwrap();
+        assert_eq!(summary.keys, 2);
+        assert_eq!(summary.files, 1);
+    }
+
+    let cf1 = db.cf_handle("cf1").unwrap();
+    let cf2 = db.cf_handle("cf2").unwrap();
+    assert_eq!(db.get_cf(&cf1, b"k1").unwrap().unwrap(), b"one");
+    assert_eq!(db.get_cf(&cf2, b"k2").unwrap().unwrap(), b"two");
+    assert!(db.get(b"k1").unwrap().is_none());
+
+    // An empty load ingests nothing.
+    let loader = BulkLoader::new(&Options::default(), &BulkLoadOptions::default()).unwrap();
+    let summary = loader.ingest_cf(&db, &cf1).unwrap();
+    assert_eq!(summary.files, 0);
+}
@@ -20,9 +20,10 @@ use pretty_assertions::assert_eq;
 
 use rocksdb::{
     perf::get_memory_usage_stats, BlockBasedOptions, BottommostLevelCompaction, Cache,
-    CompactOptions, CuckooTableOptions, DBCompactionStyle, DBWithThreadMode, Env, Error,
-    FifoCompactOptions, IteratorMode, MultiThreaded, Options, PerfContext, PerfMetric, ReadOptions,
-    SingleThreaded, SliceTransform, Snapshot, UniversalCompactOptions,
+    ColumnFamilyDescriptor, ColumnFamilyTtl, CompactOptions, CuckooTableOptions, DBCompactionStyle,
+    DBCompressionType, DBRead, DBWithThreadMode, Env, Error, FifoCompactOptions, IteratorMode,
+    MultiThreaded, Options, OwnedIterator, OwnedSnapshot, PerfContext, PerfMetric, ReadOnlyDB,
+    ReadOptions, SecondaryDB, SingleThreaded, SliceTransform, Snapshot, UniversalCompactOptions,
     UniversalCompactionStopStyle, WriteBatch, DB,
 };
 use util::DBPath;
@@ -300,6 +301,50 @@ fn sync_snapshot_test() {
     assert!(handler_2.join().unwrap());
 }
 
+#[test]
+fn owned_snapshot_test() {
+    let path = DBPath::new("_rust_rocksdb_owned_snapshottest");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = Arc::new(DB::open_cf(&opts, &path, &["cf1"]).unwrap());
+
+    db.put(b"k1", b"v1").unwrap();
+    db.put_cf(&db.cf_handle("cf1").unwrap(), b"k1", b"cf_v1")
+        .unwrap();
+    let snapshot = OwnedSnapshot::new(db.clone());
+    let iter = snapshot.iterator(IteratorMode::Start);
+    let mut raw_iter = snapshot.raw_iterator();
+    db.put(b"k2", b"v2").unwrap();
+    db.put_cf(&db.cf_handle("cf1").unwrap(), b"k2", b"cf_v2")
+        .unwrap();
+
+    let snapshot_1 = snapshot.clone();
+    let handler_1 = thread::spawn(move || {
+        let cf = snapshot_1.db().cf_handle("cf1").unwrap();
+        assert_eq!(snapshot_1.get_cf(&cf, b"k1").unwrap().unwrap(), b"cf_v1");
+        assert!(snapshot_1.get_cf(&cf, b"k2").unwrap().is_none());
+        let mut raw_iter = snapshot_1.raw_iterator_cf(&cf);
+        raw_iter.seek_to_last();
+        assert_eq!(raw_iter.value(), Some(&b"cf_v1"[..]));
+        snapshot_1.iterator_cf(&cf, IteratorMode::End).count()
+    });
+    // The snapshot stays alive through the iterators.
+    drop(snapshot);
+    let handler_2 = thread::spawn(move || iter.count());
+    let handler_3 = thread::spawn(move || {
+        raw_iter.seek_to_last();
+        raw_iter.key().map(<[u8]>::to_vec)
+    });
+
+    assert_eq!(handler_1.join().unwrap(), 1);
+    assert_eq!(handler_2.join().unwrap(), 1);
+    assert_eq!(handler_3.join().unwrap().unwrap(), b"k1");
+
+    let iter = OwnedIterator::new(db.clone(), ReadOptions::default(), IteratorMode::Start);
+    assert_eq!(thread::spawn(move || iter.count()).join().unwrap(), 2);
+}
+
 #[test]
 fn set_option_test() {
     let path = DBPath::new("_rust_rocksdb_set_optionstest");
@@ -337,6 +382,56 @@ fn set_option_test() {
     }
 }
 
+#[test]
+fn blob_files_test() {
+    let path = DBPath::new("_rust_rocksdb_blob_files_test");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let mut cf_opts = Options::default();
+        cf_opts.set_enable_blob_files(true);
+        cf_opts.set_min_blob_size(1024);
+        cf_opts.set_blob_file_size(1 << 20);
+        cf_opts.set_blob_compression_type(DBCompressionType::None);
+        cf_opts.set_enable_blob_gc(true);
+        cf_opts.set_blob_gc_age_cutoff(0.5);
+        assert!(cf_opts.get_enable_blob_files());
+        assert_eq!(cf_opts.get_min_blob_size(), 1024);
+        assert_eq!(
+            cf_opts.get_blob_compression_type(),
+            Some(DBCompressionType::None)
+        );
+        assert!((cf_opts.get_blob_gc_age_cutoff() - 0.5).abs() < f64::EPSILON);
+
+        let cfs = vec![ColumnFamilyDescriptor::new("blobs", cf_opts)];
+        let db = DB::open_cf_descriptors(&opts, &path, cfs).unwrap();
+        let cf = db.cf_handle("blobs").unwrap();
+        let large = vec![b'x'; 4096];
+        db.put_cf(&cf, b"large", &large).unwrap();
+        db.put_cf(&cf, b"small", b"v").unwrap();
+        db.flush_cf(&cf).unwrap();
+
+        let has_blob_file = std::fs::read_dir(&path)
+            .unwrap()
+            .any(|entry| entry.unwrap().path().extension() == Some("blob".as_ref()));
+        assert!(has_blob_file);
+        assert_eq!(db.get_cf(&cf, b"large").unwrap().unwrap(), large);
+        assert_eq!(db.get_cf(&cf, b"small").unwrap().unwrap(), b"v");
+
+        assert!(db
+            .set_options_cf(
+                &cf,
+                &[
+                    ("min_blob_size", "8192"),
+                    ("enable_blob_garbage_collection", "false"),
+                    ("blob_garbage_collection_age_cutoff", "0.75"),
+                ]
+            )
+            .is_ok());
+    }
+}
+
 #[test]
 fn set_option_cf_test() {
     let path = DBPath::new("_rust_rocksdb_set_options_cftest");
@@ -469,6 +564,63 @@ fn test_get_updates_since_one_batch() {
     assert_eq!(counts.deletes, 1);
 }
 
+struct ReplayBatch<'a> {
+    db: &'a DB,
+    batch: WriteBatch,
+}
+
+impl<'a> rocksdb::WriteBatchIteratorCf for ReplayBatch<'a> {
+    fn put_cf(&mut self, cf_id: u32, key: &[u8], value: &[u8]) {
+        let name = self.db.cf_name_by_id(cf_id).unwrap().unwrap();
+        self.batch
+            .put_cf(&self.db.cf_handle(&name).unwrap(), key, value);
+    }
+    fn merge_cf(&mut self, cf_id: u32, key: &[u8], value: &[u8]) {
+        let name = self.db.cf_name_by_id(cf_id).unwrap().unwrap();
+        self.batch
+            .merge_cf(&self.db.cf_handle(&name).unwrap(), key, value);
+    }
+    fn delete_cf(&mut self, cf_id: u32, key: &[u8]) {
+        let name = self.db.cf_name_by_id(cf_id).unwrap().unwrap();
+        self.batch
+            .delete_cf(&self.db.cf_handle(&name).unwrap(), key);
+    }
+    fn single_delete_cf(&mut self, cf_id: u32, key: &[u8]) {
+        let name = self.db.cf_name_by_id(cf_id).unwrap().unwrap();
+        self.batch
+            .single_delete_cf(&self.db.cf_handle(&name).unwrap(), key);
+    }
+    fn delete_range_cf(&mut self, cf_id: u32, from: &[u8], to: &[u8]) {
+        let name = self.db.cf_name_by_id(cf_id).unwrap().unwrap();
+        self.batch
+            .delete_range_cf(&self.db.cf_handle(&name).unwrap(), from, to);
+    }
+}
+
+#[test]
+fn test_get_updates_since_replay_cf() {
+    let path = DBPath::new("_rust_rocksdb_test_get_updates_since_replay_cf");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+    let cf1 = db.cf_handle("cf1").unwrap();
+    let mut batch = WriteBatch::default();
+    batch.put_cf(&cf1, b"key1", b"value1");
+    batch.put(b"key2", b"value2");
+    batch.delete_range_cf(&cf1, b"key0", b"key1");
+    db.write(batch).unwrap();
+
+    let mut iter = db.get_updates_since(0).unwrap();
+    let (_, batch) = iter.next().unwrap();
+    let mut replay = ReplayBatch {
+        db: &db,
+        batch: WriteBatch::default(),
+    };
+    batch.iterate_cf(&mut replay).unwrap();
+    assert_eq!(replay.batch.data()[8..], batch.data()[8..]);
+}
+
 #[test]
 fn test_get_updates_since_nothing() {
     let path = DBPath::new("_rust_rocksdb_test_get_updates_since_nothing");
@@ -513,6 +665,32 @@ fn test_open_as_secondary() {
     assert_eq!(get_byte_slice(&result), b"value2");
 }
 
+#[test]
+fn test_secondary_db() {
+    let primary_path = DBPath::new("_rust_rocksdb_test_secondary_db_primary");
+
+    let db = DB::open_default(&primary_path).unwrap();
+    db.put(b"key1", b"value1").unwrap();
+
+    let mut opts = Options::default();
+    opts.set_max_open_files(-1);
+
+    let secondary_path = DBPath::new("_rust_rocksdb_test_secondary_db_secondary");
+    let secondary = SecondaryDB::open(&opts, &primary_path, &secondary_path).unwrap();
+    assert_eq!(secondary.get(b"key1").unwrap().unwrap(), b"value1");
+
+    db.put(b"key1", b"value2").unwrap();
+    secondary.try_catch_up_with_primary().unwrap();
+    assert_eq!(secondary.get(b"key1").unwrap().unwrap(), b"value2");
+
+    // Generic code accepts any handle through the shared read trait.
+    fn count_keys(db: &impl DBRead) -> usize {
+        db.iterator(IteratorMode::Start).count()
+    }
+    assert_eq!(count_keys(&secondary), 1);
+    assert_eq!(count_keys(&db), 1);
+}
+
 #[test]
 fn test_open_with_ttl() {
     let path = DBPath::new("_rust_rocksdb_test_open_with_ttl");
@@ -548,6 +726,104 @@ fn test_open_cf_with_ttl() {
     assert!(db.get_cf(&cf, b"key1").unwrap().is_none());
 }
 
+#[test]
+fn test_open_cf_descriptors_with_ttl() {
+    let path = DBPath::new("_rust_rocksdb_test_open_cf_descriptors_with_ttl");
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let cfs = vec![
+        ColumnFamilyDescriptor::new("same_as_db", Options::default()),
+        ColumnFamilyDescriptor::new_with_ttl(
+            "disabled",
+            Options::default(),
+            ColumnFamilyTtl::Disabled,
+        ),
+        ColumnFamilyDescriptor::new_with_ttl(
+            "one_hour",
+            Options::default(),
+            ColumnFamilyTtl::Enabled(Duration::from_secs(3600)),
+        ),
+    ];
+    let db = DB::open_cf_descriptors_with_ttl(&opts, &path, cfs, Duration::from_secs(1)).unwrap();
+    for name in &["same_as_db", "disabled", "one_hour"] {
+        let cf = db.cf_handle(name).unwrap();
+        db.put_cf(&cf, b"key1", b"value1").unwrap();
+    }
+
+    thread::sleep(Duration::from_secs(2));
+    for name in &["same_as_db", "disabled", "one_hour"] {
+        let cf = db.cf_handle(name).unwrap();
+        db.compact_range_cf(&cf, None::<&[u8]>, None::<&[u8]>);
+    }
+
+    let expired = |name| {
+        let cf = db.cf_handle(name).unwrap();
+        db.get_cf(&cf, b"key1").unwrap().is_none()
+    };
+    assert!(expired("same_as_db"));
+    assert!(!expired("disabled"));
+    assert!(!expired("one_hour"));
+}
+
+#[test]
+fn test_periodic_compaction_and_ttl() {
+    let path = DBPath::new("_rust_rocksdb_test_periodic_compaction_and_ttl");
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_max_open_files(-1);
+    assert_eq!(opts.get_ttl(), None);
+    opts.set_ttl(30 * 24 * 60 * 60).unwrap();
+    opts.set_periodic_compaction_seconds(7 * 24 * 60 * 60)
+        .unwrap();
+    assert_eq!(opts.get_ttl(), Some(30 * 24 * 60 * 60));
+    assert_eq!(
+        opts.get_periodic_compaction_seconds(),
+        Some(7 * 24 * 60 * 60)
+    );
+    let db = DB::open(&opts, &path).unwrap();
+
+    // Read back the values RocksDB wrote to its OPTIONS file.
+    let persisted = || {
+        let env = Env::default().unwrap();
+        let cache = Cache::new_lru_cache(1 << 20).unwrap();
+        let (opts, _) = Options::load_latest(&path, &env, &cache).unwrap();
+        (opts.get_ttl(), opts.get_periodic_compaction_seconds())
+    };
+    assert_eq!(
+        persisted(),
+        (Some(30 * 24 * 60 * 60), Some(7 * 24 * 60 * 60))
+    );
+
+    db.put(b"key1", b"value1").unwrap();
+    db.set_options(&[("periodic_compaction_seconds", "3600"), ("ttl", "0")])
+        .unwrap();
+    assert_eq!(persisted(), (Some(0), Some(3600)));
+    assert!(db
+        .set_options(&[("periodic_compaction_seconds", "never")])
+        .is_err());
+    assert_eq!(db.get(b"key1").unwrap().unwrap(), b"value1");
+}
+
+#[test]
+fn test_open_with_too_long_ttl() {
+    let path = DBPath::new("_rust_rocksdb_test_open_with_too_long_ttl");
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let ttl = Duration::from_secs(i32::MAX as u64 + 1);
+    assert!(DB::open_with_ttl(&opts, &path, ttl).is_err());
+    let cfs = vec![ColumnFamilyDescriptor::new_with_ttl(
+        "cf1",
+        Options::default(),
+        ColumnFamilyTtl::Enabled(ttl),
+    )];
+    opts.create_missing_column_families(true);
+    assert!(DB::open_cf_descriptors_with_ttl(&opts, &path, cfs, Duration::from_secs(1)).is_err());
+}
+
 #[test]
 fn test_open_as_single_threaded() {
     let primary_path = DBPath::new("_rust_rocksdb_test_open_as_single_threaded");
@@ -750,6 +1026,7 @@ fn get_with_cache_and_bulkload_test() {
         // set block based table and cache
         let cache = Cache::new_lru_cache(512 << 10).unwrap();
         assert_eq!(cache.get_usage(), 0);
+        assert_eq!(cache.get_capacity(), 512 << 10);
         let mut block_based_opts = BlockBasedOptions::default();
         block_based_opts.set_block_cache(&cache);
         block_based_opts.set_cache_index_and_filter_blocks(true);
@@ -893,6 +1170,35 @@ fn test_open_cf_for_read_only() {
     }
 }
 
+#[test]
+fn test_read_only_db() {
+    let path = DBPath::new("_rust_rocksdb_test_read_only_db");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.put_cf(&cf1, b"k1", b"cf_v1").unwrap();
+    }
+    {
+        let opts = Options::default();
+        let db = ReadOnlyDB::open_cf(&opts, &path, &["cf1"], false).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        assert_eq!(db.get(b"k1").unwrap().unwrap(), b"v1");
+        assert_eq!(db.get_cf(&cf1, b"k1").unwrap().unwrap(), b"cf_v1");
+        assert_eq!(db.iterator_cf(&cf1, IteratorMode::Start).count(), 1);
+        assert_eq!(db.latest_sequence_number(), 2);
+    }
+}
+
+#[test]
+fn test_read_only_db_put() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/fail/read_only_put.rs");
+}
+
 #[test]
 fn delete_range_test() {
     let path = DBPath::new("_rust_rocksdb_delete_range_test");
@@ -973,6 +1279,81 @@ fn multi_get_cf() {
     }
 }
 
+#[test]
+fn get_into_and_get_with() {
+    let path = DBPath::new("_rust_rocksdb_get_into_and_get_with");
+
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.put_cf(&cf1, b"k2", b"value2").unwrap();
+
+        let mut value = b"previous value".to_vec();
+        assert!(db.get_into(b"k1", &mut value).unwrap());
+        assert_eq!(value, b"v1");
+        assert!(!db.get_into(b"k0", &mut value).unwrap());
+        assert!(value.is_empty());
+        assert!(db.get_into_cf(&cf1, b"k2", &mut value).unwrap());
+        assert_eq!(value, b"value2");
+
+        assert_eq!(db.get_with(b"k1", <[u8]>::len).unwrap(), Some(2));
+        assert_eq!(db.get_with(b"k0", <[u8]>::len).unwrap(), None);
+        assert_eq!(
+            db.get_with_cf(&cf1, b"k2", |v| v == b"value2").unwrap(),
+            Some(true)
+        );
+    }
+}
+
+#[test]
+fn multi_get_into_and_multi_get_with() {
+    let path = DBPath::new("_rust_rocksdb_multi_get_into_and_multi_get_with");
+
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.put(b"k2", b"v2").unwrap();
+        db.put_cf(&cf1, b"k3", b"v3").unwrap();
+
+        let mut values = vec![b"stale".to_vec(); 5];
+        let found = db
+            .multi_get_into(&[b"k0", b"k1", b"k2"], &mut values)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec![false, true, true]);
+        assert_eq!(values, vec![b"".to_vec(), b"v1".to_vec(), b"v2".to_vec()]);
+
+        let found = db
+            .multi_get_into_cf(vec![(&cf1, b"k3"), (&cf1, b"k1")], &mut values)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec![true, false]);
+        assert_eq!(values, vec![b"v3".to_vec(), b"".to_vec()]);
+
+        let mut lengths = Vec::new();
+        db.multi_get_with(&[b"k1", b"k0"], |i, value| {
+            lengths.push((i, value.unwrap().map(<[u8]>::len)));
+        });
+        assert_eq!(lengths, vec![(0, Some(2)), (1, None)]);
+
+        let mut seen = Vec::new();
+        db.multi_get_with_cf(vec![(&cf1, b"k3")], |i, value| {
+            seen.push((i, value.unwrap().map(<[u8]>::to_vec)));
+        });
+        assert_eq!(seen, vec![(0, Some(b"v3".to_vec()))]);
+    }
+}
+
 #[test]
 fn key_may_exist() {
     let path = DBPath::new("_rust_key_may_exist");
@@ -0,0 +1,141 @@
+// Copyright 2021 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "cli")]
+
+mod util;
+
+use std::path::Path;
+use std::process::Command;
+
+use pretty_assertions::assert_eq;
+
+use rocksdb::{Options, DB};
+use util::DBPath;
+
+fn ldb<P: AsRef<Path>>(db: P, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_rocksdb-ldb"))
+        .arg("--db")
+        .arg(db.as_ref())
+        .args(args)
+        .output()
+        .unwrap();
+    if output.status.success() {
+        Ok(String::from_utf8(output.stdout).unwrap())
+    } else {
+        Err(String::from_utf8(output.stderr).unwrap())
+    }
+}
+
+fn create_db<P: AsRef<Path>>(path: P) {
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    DB::open_cf(&opts, path, &["cf1"]).unwrap();
+}
+
+#[test]
+fn test_ldb_commands() {
+    let path = DBPath::new("_rust_rocksdb_test_ldb_commands");
+    create_db(&path);
+
+    assert_eq!(ldb(&path, &["list-cf"]).unwrap(), "default\ncf1\n");
+
+    ldb(&path, &["put", "--cf", "cf1", "a1", "one"]).unwrap();
+    ldb(
+        &path,
+        &["put", "--cf", "cf1", "--key-format", "hex", "6132", "two"],
+    )
+    .unwrap();
+    ldb(&path, &["put", "--cf", "cf1", "b\\x00", "three"]).unwrap();
+    ldb(&path, &["put", "c", "default"]).unwrap();
+
+    assert_eq!(ldb(&path, &["get", "--cf", "cf1", "a2"]).unwrap(), "two\n");
+    assert!(ldb(&path, &["get", "a1"]).is_err());
+    assert_eq!(
+        ldb(&path, &["scan", "--cf", "cf1"]).unwrap(),
+        "a1 : one\na2 : two\nb\\x00 : three\n"
+    );
+    assert_eq!(
+        ldb(
+            &path,
+            &["scan", "--cf", "cf1", "--prefix", "a", "--keys-only"]
+        )
+        .unwrap(),
+        "a1\na2\n"
+    );
+    assert_eq!(
+        ldb(
+            &path,
+            &[
+                "--key-format",
+                "hex",
+                "scan",
+                "--cf",
+                "cf1",
+                "--from",
+                "6132",
+                "--limit",
+                "1"
+            ]
+        )
+        .unwrap(),
+        "6132 : two\n"
+    );
+    assert_eq!(
+        ldb(&path, &["scan", "--cf", "cf1", "--to", "a2"]).unwrap(),
+        "a1 : one\n"
+    );
+
+    ldb(&path, &["delete", "--cf", "cf1", "a1"]).unwrap();
+    assert!(ldb(&path, &["get", "--cf", "cf1", "a1"]).is_err());
+    ldb(&path, &["compact", "--cf", "cf1"]).unwrap();
+    assert_eq!(
+        ldb(
+            &path,
+            &["property", "--cf", "cf1", "rocksdb.estimate-num-keys"]
+        )
+        .unwrap(),
+        "2\n"
+    );
+
+    // Reads work read-only, writes don't.
+    assert_eq!(
+        ldb(&path, &["--read-only", "get", "c"]).unwrap(),
+        "default\n"
+    );
+    assert!(ldb(&path, &["--read-only", "put", "c", "x"]).is_err());
+}
+
+#[test]
+fn test_ldb_dump_load() {
+    let source = DBPath::new("_rust_rocksdb_test_ldb_dump_source");
+    let target = DBPath::new("_rust_rocksdb_test_ldb_dump_target");
+    create_db(&source);
+    create_db(&target);
+
+    let dump_dir = tempfile::tempdir().unwrap();
+    let dump = dump_dir.path().join("dump");
+    let dump = dump.to_str().unwrap();
+
+    ldb(&source, &["put", "--cf", "cf1", "k1", "\\xff\\x00"]).unwrap();
+    ldb(&source, &["put", "--cf", "cf1", "k2", "v2"]).unwrap();
+    ldb(&source, &["dump", "--cf", "cf1", "--output", dump]).unwrap();
+    ldb(&target, &["load", "--cf", "cf1", "--input", dump]).unwrap();
+
+    assert_eq!(
+        ldb(&target, &["scan", "--cf", "cf1", "--value-format", "hex"]).unwrap(),
+        "k1 : ff00\nk2 : 7632\n"
+    );
+}
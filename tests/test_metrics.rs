@@ -0,0 +1,120 @@
+// Copyright 2021 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "metrics")]
+
+mod util;
+
+use pretty_assertions::assert_eq;
+
+use rocksdb::{
+    metrics::MetricsRenderer, Cache, DBRead, DBWithThreadMode, MultiThreaded, Options, ReadOnlyDB,
+    DB,
+};
+use util::DBPath;
+
+#[test]
+fn test_metrics_render() {
+    let path = DBPath::new("_rust_rocksdb_test_metrics_render");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    opts.enable_statistics();
+    let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+    let cf1 = db.cf_handle("cf1").unwrap();
+    db.put_cf(&cf1, b"k1", b"v1").unwrap();
+    db.put_cf(&cf1, b"k2", b"v2").unwrap();
+    db.flush_cf(&cf1).unwrap();
+    db.get_cf(&cf1, b"k1").unwrap();
+    let cache = Cache::new_lru_cache(1024 * 1024).unwrap();
+
+    let mut renderer = MetricsRenderer::new();
+    renderer.set_prefix("app.rocksdb");
+    renderer.add_label("service", "a \"quoted\" name");
+    renderer.add_db_with_statistics(&db, &opts);
+    renderer.add_cache("shared", &cache);
+    let text = renderer.render().unwrap();
+
+    let db_path = db.path().to_str().unwrap();
+    let cf1_labels = format!(
+        "service=\"a \\\"quoted\\\" name\",db=\"{}\",cf=\"cf1\"",
+        db_path
+    );
+    let lines: Vec<&str> = text.lines().collect();
+
+    // Each family is described once, before its samples.
+    assert_eq!(
+        lines
+            .iter()
+            .filter(|line| **line == "# TYPE app_rocksdb_estimate_num_keys gauge")
+            .count(),
+        1
+    );
+    assert!(lines.contains(&format!("app_rocksdb_estimate_num_keys{{{}}} 2", cf1_labels).as_str()));
+    assert!(lines.contains(
+        &format!(
+            "app_rocksdb_num_files_at_level{{{},level=\"0\"}} 1",
+            cf1_labels
+        )
+        .as_str()
+    ));
+    assert!(text.contains("app_rocksdb_estimate_num_keys{service=\"a \\\"quoted\\\" name\",db="));
+    assert!(text.contains(",cf=\"default\"} 0\n"));
+    assert!(text.contains("# TYPE app_rocksdb_number_keys_written_total counter\n"));
+    assert!(text.contains("# TYPE app_rocksdb_db_get_micros summary\n"));
+    assert!(text.contains("app_rocksdb_db_get_micros_count{"));
+    assert!(text.contains("quantile=\"0.99\"} "));
+    assert!(text.contains("app_rocksdb_memory_mem_table_total_bytes{"));
+    assert!(text.contains(
+        "app_rocksdb_cache_capacity_bytes{service=\"a \\\"quoted\\\" name\",cache=\"shared\"} 1048576\n"
+    ));
+
+    // Every sample belongs to a described family.
+    for line in lines.iter().filter(|line| !line.starts_with('#')) {
+        assert!(line.starts_with("app_rocksdb_"), "{}", line);
+    }
+}
+
+#[test]
+fn test_metrics_render_other_handles() {
+    let path = DBPath::new("_rust_rocksdb_test_metrics_render_other_handles");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    {
+        let db = DBWithThreadMode::<MultiThreaded>::open_cf(&opts, &path, &["cf1"]).unwrap();
+        db.put_cf(&db.cf_handle("cf1").unwrap(), b"k1", b"v1")
+            .unwrap();
+
+        let mut renderer = MetricsRenderer::new();
+        renderer.add_db(&db);
+        let text = renderer.render().unwrap();
+        let sample = format!(
+            "rocksdb_estimate_num_keys{{db=\"{}\",cf=\"cf1\"}} 1",
+            db.path().to_str().unwrap()
+        );
+        assert!(text.lines().any(|line| line == sample), "{}", text);
+        assert!(text.contains("rocksdb_memory_mem_table_total_bytes{"));
+    }
+
+    let db = ReadOnlyDB::open_cf(&opts, &path, &["cf1"], false).unwrap();
+    let mut renderer = MetricsRenderer::new();
+    renderer.add_db(&db);
+    let text = renderer.render().unwrap();
+    let sample = format!(
+        "rocksdb_estimate_num_keys{{db=\"{}\",cf=\"cf1\"}} 1",
+        db.path().to_str().unwrap()
+    );
+    assert!(text.lines().any(|line| line == sample), "{}", text);
+}
@@ -0,0 +1,90 @@
+// Copyright 2021 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod util;
+
+use std::sync::Arc;
+
+use pretty_assertions::assert_eq;
+
+use rocksdb::{KeyRange, Options, ParallelScanner, Range, DB};
+use util::DBPath;
+
+#[test]
+fn test_parallel_scan_cf() {
+    let path = DBPath::new("_rust_rocksdb_test_parallel_scan_cf");
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = Arc::new(DB::open_cf(&opts, &path, &["cf1"]).unwrap());
+    let cf1 = db.cf_handle("cf1").unwrap();
+    for i in 0..10_000u32 {
+        db.put_cf(&cf1, i.to_be_bytes(), [0; 100]).unwrap();
+        // Flush regularly so that there are SST file boundaries to split at.
+        if i % 1000 == 999 {
+            db.flush_cf(&cf1).unwrap();
+        }
+    }
+
+    let sizes = db
+        .get_approximate_sizes_cf(&cf1, &[Range::new(&[0; 4], &[0xff; 4])])
+        .unwrap();
+    assert!(sizes[0] > 0);
+
+    let mut scanner = ParallelScanner::new(db.clone(), 4);
+    scanner.set_threads(2);
+    let shards = scanner.shards_cf(&cf1).unwrap();
+    assert!(shards.len() > 1 && shards.len() <= 4);
+    assert_eq!(shards.first().unwrap().start, None);
+    assert_eq!(shards.last().unwrap().end, None);
+    for pair in shards.windows(2) {
+        assert_eq!(pair[0].end, pair[1].start);
+    }
+
+    let results = scanner
+        .scan_cf(&cf1, |shard, range: &KeyRange, iter| {
+            let keys: Vec<_> = iter.map(|(key, _)| key).collect();
+            for key in &keys {
+                assert!(range
+                    .start
+                    .as_deref()
+                    .map_or(true, |start| &key[..] >= start));
+                assert!(range.end.as_deref().map_or(true, |end| &key[..] < end));
+            }
+            (shard, keys)
+        })
+        .unwrap();
+
+    // Results come back in shard order and cover every key exactly once.
+    let mut expected = 0u32;
+    for (i, (shard, keys)) in results.into_iter().enumerate() {
+        assert_eq!(shard, i);
+        for key in keys {
+            assert_eq!(&key[..], &expected.to_be_bytes()[..]);
+            expected += 1;
+        }
+    }
+    assert_eq!(expected, 10_000);
+
+    // The default column family is empty.
+    let counts = scanner.scan(|_, _, iter| iter.count()).unwrap();
+    assert_eq!(counts.iter().sum::<usize>(), 0);
+}
+
+#[test]
+fn test_shard_iterator_escape_scan() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/fail/shard_iterator_escape_scan.rs");
+}
@@ -16,7 +16,10 @@ mod util;
 
 use pretty_assertions::assert_eq;
 
-use rocksdb::{Options, DB};
+use rocksdb::{
+    properties::{self, CfStats, LevelStats, PropertyType, TableProperties},
+    Options, DB,
+};
 use util::DBPath;
 
 #[test]
@@ -72,3 +75,160 @@ fn property_int_cf_test() {
         assert_eq!(total_keys, Some(0));
     }
 }
+
+#[test]
+fn property_typed_test() {
+    let n = DBPath::new("_rust_rocksdb_property_typed_test");
+    {
+        let db = DB::open_default(&n).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.put(b"k2", b"v2").unwrap();
+        db.flush().unwrap();
+
+        assert_eq!(
+            properties::ESTIMATE_NUM_KEYS.name(),
+            "rocksdb.estimate-num-keys"
+        );
+        assert_eq!(
+            db.property_int_value(properties::ESTIMATE_NUM_KEYS)
+                .unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            db.property_int_value(properties::num_files_at_level(0))
+                .unwrap(),
+            Some(1)
+        );
+
+        let levels: LevelStats = db
+            .property_value(properties::LEVELSTATS)
+            .unwrap()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(levels.levels[0].level, 0);
+        assert_eq!(levels.levels[0].files, 1);
+        assert!(levels.levels[1..].iter().all(|level| level.files == 0));
+
+        assert_eq!(properties::CFSTATS.value_type(), PropertyType::String);
+        let stats: CfStats = db
+            .property_value(properties::CFSTATS)
+            .unwrap()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let l0 = stats.level("L0").unwrap();
+        assert_eq!(l0.files, 1);
+        assert!(l0.size_bytes > 0);
+        assert!(stats.level("Sum").is_some());
+    }
+}
+
+#[test]
+fn property_map_test() {
+    let n = DBPath::new("_rust_rocksdb_property_map_test");
+    {
+        let db = DB::open_default(&n).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.delete(b"k2").unwrap();
+        db.flush().unwrap();
+
+        let map = db
+            .property_map_value(properties::AGGREGATED_TABLE_PROPERTIES)
+            .unwrap()
+            .unwrap();
+        assert_eq!(map["# entries"], "2");
+        assert_eq!(map["# deletions"], "1");
+
+        let props: TableProperties = db
+            .property_value(properties::aggregated_table_properties_at_level(0))
+            .unwrap()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(props.num_entries, 2);
+        assert_eq!(props.num_deletions, 1);
+        assert_eq!(props.raw_key_size, 2 * (2 + 8));
+    }
+}
+
+#[test]
+fn property_parse_test() {
+    let map = "# entries=3; SST file compression options=window_bits=-14; level=32767; \
+               enabled=0; ; creation time=5; ";
+    let stats: TableProperties = format!(
+        "# data blocks=1; {}# deletions=0; # merge operands=0; # range deletions=0; \
+         raw key size=30; raw value size=6; data block size=100; \
+         index block size (user-key? 1, delta-value? 1)=20; filter block size=0; ",
+        map
+    )
+    .parse()
+    .unwrap();
+    assert_eq!(stats.num_entries, 3);
+    assert_eq!(stats.index_size, 20);
+
+    let stats: CfStats = "\n** Compaction Stats [default] **\n\
+        Level    Files   Size     Score Read(GB)  Rn(GB) Rnp1(GB) Write(GB) Wnew(GB) Moved(GB) \
+        W-Amp Rd(MB/s) Wr(MB/s) Comp(sec) CompMergeCPU(sec) Comp(cnt) Avg(sec) KeyIn KeyDrop \
+        Rblob(GB) Wblob(GB)\n\
+        ----------\n  \
+          L0      2/1    1.50 MB   0.5      0.0     0.0      0.0       0.0      0.0       0.0   \
+        1.0      0.0      1.2      0.01              0.00         1    0.010   12K      3       \
+        0.0       0.0\n\
+         Sum      2/1    1.50 MB   0.0      0.0     0.0      0.0       0.0      0.0       0.0   \
+        1.0      0.0      1.2      0.01              0.00         1    0.010   12K      3       \
+        0.0       0.0\n\n"
+        .parse()
+        .unwrap();
+    let l0 = stats.level("L0").unwrap();
+    assert_eq!(l0.files, 2);
+    assert_eq!(l0.compacting_files, 1);
+    assert_eq!(l0.size_bytes, 3 * 512 * 1024);
+    assert_eq!(l0.key_in, 12_000);
+    assert_eq!(l0.key_drop, 3);
+    assert_eq!(stats.levels.len(), 2);
+}
+
+#[test]
+fn typed_property_test() {
+    let n = DBPath::new("_rust_rocksdb_typed_property_test");
+    {
+        let opts = Options::default();
+        let mut db = DB::open_default(&n).unwrap();
+        db.create_cf("cf1", &opts).unwrap();
+        let cf = db.cf_handle("cf1").unwrap();
+        db.put_cf(&cf, b"key", b"value").unwrap();
+        db.flush_cf(&cf).unwrap();
+
+        assert_eq!(
+            db.int_property_cf(&cf, &properties::ESTIMATE_NUM_KEYS)
+                .unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            db.int_property(&properties::num_files_at_level(0)).unwrap(),
+            Some(0)
+        );
+        assert!(db
+            .string_property_cf(&cf, &properties::LEVELSTATS)
+            .unwrap()
+            .unwrap()
+            .contains("Level"));
+        assert!(db
+            .map_property_cf(&cf, &properties::AGGREGATED_TABLE_PROPERTIES)
+            .unwrap()
+            .unwrap()
+            .contains_key("# entries"));
+
+        // Properties of another type are rejected.
+        assert!(db.int_property(&properties::LEVELSTATS).is_err());
+        assert!(db.string_property(&properties::ESTIMATE_NUM_KEYS).is_err());
+        assert!(db.map_property(&properties::CFSTATS).is_err());
+    }
+}
+
+#[test]
+fn property_name_string_test() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/fail/property_name_string.rs");
+}
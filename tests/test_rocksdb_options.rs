@@ -16,7 +16,11 @@ mod util;
 
 use std::{fs, io::Read as _};
 
-use rocksdb::{BlockBasedOptions, DataBlockIndexType, Options, ReadOptions, DB};
+use rocksdb::{
+    BlockBasedIndexType, BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBCompactionStyle,
+    DBCompressionType, DataBlockIndexType, Env, FilterPolicy, LogLevel, MergeOperands, OptionDiff,
+    Options, OptionsLoader, RateLimiter, ReadOptions, SliceTransform, DB,
+};
 use util::DBPath;
 
 #[test]
@@ -146,3 +150,423 @@ fn set_compression_options_zstd_max_train_bytes() {
         let _db = DB::open(&opts, &path).unwrap();
     }
 }
+
+fn concat_merge(
+    _key: &[u8],
+    existing: Option<&[u8]>,
+    operands: &mut MergeOperands,
+) -> Option<Vec<u8>> {
+    let mut result = existing.map(<[u8]>::to_vec).unwrap_or_default();
+    for op in operands {
+        result.extend_from_slice(op);
+    }
+    Some(result)
+}
+
+#[test]
+fn test_load_latest_options() {
+    let path = DBPath::new("_rust_rocksdb_test_load_latest_options");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        opts.set_max_background_jobs(3);
+        let mut cf_opts = Options::default();
+        cf_opts.set_write_buffer_size(8 << 20);
+        cf_opts.set_num_levels(4);
+        cf_opts.set_merge_operator_associative("concat", concat_merge);
+        let cfs = vec![ColumnFamilyDescriptor::new("cf1", cf_opts)];
+        let db = DB::open_cf_descriptors(&opts, &path, cfs).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        db.merge_cf(&cf1, b"k", b"a").unwrap();
+    }
+
+    let env = Env::default().unwrap();
+    let cache = Cache::new_lru_cache(1 << 20).unwrap();
+    match Options::load_latest(&path, &env, &cache) {
+        Err(e) => assert!(e.to_string().contains("concat")),
+        Ok(_) => panic!("loading without the merge operator registered must fail"),
+    }
+
+    let mut loader = OptionsLoader::default();
+    loader.register_merge_operator("concat", |opts| {
+        opts.set_merge_operator_associative("concat", concat_merge)
+    });
+    let (opts, cfs) = loader.load_latest(&path, &env, &cache).unwrap();
+    let names: Vec<&str> = cfs.iter().map(|cf| cf.name()).collect();
+    assert_eq!(names, vec!["default", "cf1"]);
+
+    let db = DB::open_cf_descriptors(&opts, &path, cfs).unwrap();
+    let cf1 = db.cf_handle("cf1").unwrap();
+    db.merge_cf(&cf1, b"k", b"b").unwrap();
+    assert_eq!(db.get_cf(&cf1, b"k").unwrap().unwrap(), b"ab");
+}
+
+#[test]
+fn test_persist_options_to_file() {
+    let path = DBPath::new("_rust_rocksdb_test_persist_options_to_file");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_max_open_files(123);
+    let mut cf_opts = Options::default();
+    cf_opts.set_level_zero_file_num_compaction_trigger(7);
+    cf_opts.set_max_bytes_for_level_multiplier(4.5);
+    let mut block_opts = BlockBasedOptions::default();
+    block_opts.set_block_size(16 << 10);
+    block_opts.set_bloom_filter(12, false);
+    block_opts.set_index_type(BlockBasedIndexType::TwoLevelIndexSearch);
+    block_opts.disable_cache();
+    cf_opts.set_block_based_table_factory(&block_opts);
+    let _db = DB::open(&opts, &path).unwrap();
+
+    let file = (&path).as_ref().join("persisted-options");
+    opts.persist_to_file(&file, &[ColumnFamilyDescriptor::new("cf1", cf_opts)])
+        .unwrap();
+    let persisted = fs::read_to_string(&file).unwrap();
+    assert!(persisted.contains("max_open_files=123"));
+    assert!(persisted.contains("[CFOptions \"cf1\"]"));
+    assert!(persisted.contains("level0_file_num_compaction_trigger=7"));
+    assert!(persisted.contains("max_bytes_for_level_multiplier=4.500000"));
+    assert!(persisted.contains("[TableOptions/BlockBasedTable \"cf1\"]"));
+    assert!(persisted.contains("block_size=16384"));
+    assert!(persisted.contains("filter_policy=bloomfilter:12:false"));
+    assert!(persisted.contains("index_type=kTwoLevelIndexSearch"));
+    assert!(persisted.contains("no_block_cache=true"));
+
+    let env = Env::default().unwrap();
+    let cache = Cache::new_lru_cache(1 << 20).unwrap();
+    let (loaded, cfs) = Options::load_from_file(&file, &env, &cache).unwrap();
+    let copy = (&path).as_ref().join("persisted-options-copy");
+    loaded.persist_to_file(&copy, &cfs).unwrap();
+    assert_eq!(fs::read_to_string(&copy).unwrap(), persisted);
+}
+
+#[test]
+fn test_load_registered_filter_policy() {
+    let path = DBPath::new("_rust_rocksdb_test_load_registered_filter_policy");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let mut block_opts = BlockBasedOptions::default();
+        block_opts.set_bloom_filter(16, false);
+        opts.set_block_based_table_factory(&block_opts);
+        let _db = DB::open(&opts, &path).unwrap();
+    }
+
+    let env = Env::default().unwrap();
+    let cache = Cache::new_lru_cache(1 << 20).unwrap();
+    let file = (&path).as_ref().join("persisted-options");
+
+    // RocksDB records the bloom filter by name only.
+    let (opts, _) = Options::load_latest(&path, &env, &cache).unwrap();
+    opts.persist_to_file(&file, &[]).unwrap();
+    assert!(fs::read_to_string(&file)
+        .unwrap()
+        .contains("filter_policy=bloomfilter:10:false"));
+
+    let mut loader = OptionsLoader::default();
+    loader.register_filter_policy("rocksdb.BuiltinBloomFilter", |block_opts| {
+        block_opts.set_bloom_filter(16, false)
+    });
+    let (opts, _) = loader.load_latest(&path, &env, &cache).unwrap();
+    opts.persist_to_file(&file, &[]).unwrap();
+    assert!(fs::read_to_string(&file)
+        .unwrap()
+        .contains("filter_policy=bloomfilter:16:false"));
+}
+
+/// Returns the `name=...` line of the most recent OPTIONS file at `path`.
+fn latest_options_line(path: &DBPath, name: &str) -> String {
+    let mut files: Vec<_> = fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|file| {
+            file.file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| name.starts_with("OPTIONS-"))
+        })
+        .collect();
+    files.sort_by_key(|file| {
+        let name = file.file_name().unwrap().to_str().unwrap();
+        name["OPTIONS-".len()..].parse::<u64>().unwrap()
+    });
+    let prefix = format!("{}=", name);
+    fs::read_to_string(files.last().unwrap())
+        .unwrap()
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with(&prefix))
+        .unwrap()
+        .to_owned()
+}
+
+#[test]
+fn test_load_options_without_setter() {
+    let path = DBPath::new("_rust_rocksdb_test_load_options_without_setter");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_compression_options(-10, 5, 0, 4096);
+        let _db = DB::open(&opts, &path).unwrap();
+    }
+    let written = latest_options_line(&path, "compression_opts");
+    assert!(written.contains("4096"), "{}", written);
+
+    // compression_opts has no entry of its own and goes through RocksDB's
+    // options string parser.
+    let env = Env::default().unwrap();
+    let cache = Cache::new_lru_cache(1 << 20).unwrap();
+    let (opts, cfs) = Options::load_latest(&path, &env, &cache).unwrap();
+    {
+        let _db = DB::open_cf_descriptors(&opts, &path, cfs).unwrap();
+    }
+    assert_eq!(latest_options_line(&path, "compression_opts"), written);
+}
+
+#[test]
+fn test_check_options_compatibility() {
+    let path = DBPath::new("_rust_rocksdb_test_check_options_compatibility");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    opts.set_comparator("reverse", |a, b| b.cmp(a));
+    {
+        let cfs = vec![ColumnFamilyDescriptor::new("cf1", Options::default())];
+        let _db = DB::open_cf_descriptors(&opts, &path, cfs).unwrap();
+    }
+
+    let cfs = vec![ColumnFamilyDescriptor::new("cf1", Options::default())];
+    assert!(opts.check_compatibility(&path, &cfs).is_ok());
+    assert!(opts.check_compatibility(&path, &[]).is_err());
+    assert!(Options::default().check_compatibility(&path, &cfs).is_err());
+}
+
+#[test]
+fn test_options_getters() {
+    let mut opts = Options::default();
+    opts.set_write_buffer_size(32 << 20);
+    opts.set_max_background_jobs(6);
+    opts.set_level_zero_file_num_compaction_trigger(8);
+    opts.set_max_bytes_for_level_multiplier(8.0);
+    opts.set_compression_type(DBCompressionType::Lz4);
+    opts.set_compaction_style(DBCompactionStyle::Universal);
+    opts.set_disable_auto_compactions(true);
+    assert_eq!(opts.get_info_log_level(), LogLevel::Info);
+    opts.set_info_log_level(LogLevel::Warn);
+
+    assert_eq!(opts.get_write_buffer_size(), 32 << 20);
+    assert_eq!(opts.get_max_background_jobs(), 6);
+    assert_eq!(opts.get_level_zero_file_num_compaction_trigger(), 8);
+    assert!((opts.get_max_bytes_for_level_multiplier() - 8.0).abs() < f64::EPSILON);
+    assert_eq!(opts.get_compression_type(), Some(DBCompressionType::Lz4));
+    assert_eq!(opts.get_compaction_style(), DBCompactionStyle::Universal);
+    assert!(opts.get_disable_auto_compactions());
+    assert!(!opts.get_create_if_missing());
+    assert_eq!(opts.get_info_log_level(), LogLevel::Warn);
+}
+
+#[test]
+fn test_options_string_round_trip() {
+    let mut opts = Options::default();
+    opts.set_max_open_files(42);
+    opts.set_num_levels(5);
+    opts.set_compression_type(DBCompressionType::Zstd);
+    opts.set_prefix_extractor(SliceTransform::create_fixed_prefix(4));
+    opts.set_wal_dir("/path/to/wal");
+    opts.set_manual_wal_flush(true);
+    opts.set_compression_per_level(&[DBCompressionType::None, DBCompressionType::Zstd]);
+    opts.set_max_bytes_for_level_multiplier_additional(&[1, 2, 3]);
+    let mut block_opts = BlockBasedOptions::default();
+    block_opts.set_block_size(32 << 10);
+    block_opts.set_bloom_filter(8, false);
+    opts.set_block_based_table_factory(&block_opts);
+
+    let options_string = opts.to_options_string();
+    assert!(options_string.contains("max_open_files=42;"));
+    assert!(options_string.contains("compression=kZSTD;"));
+    assert!(options_string.contains("prefix_extractor=rocksdb.FixedPrefix.4;"));
+    assert!(options_string.contains("wal_dir=/path/to/wal;"));
+    assert!(options_string.contains("manual_wal_flush=true;"));
+    assert!(options_string.contains("compression_per_level=kNoCompression:kZSTD;"));
+    assert!(options_string.contains("max_bytes_for_level_multiplier_additional=1:2:3;"));
+    assert!(options_string.contains(
+        "block_based_table_factory={block_size=32768;filter_policy=bloomfilter:8:false;};"
+    ));
+
+    let restored = Options::from_options_string(&options_string).unwrap();
+    assert_eq!(restored.get_max_open_files(), 42);
+    assert_eq!(restored.get_num_levels(), 5);
+    assert_eq!(
+        restored.get_wal_dir(),
+        Some(std::path::Path::new("/path/to/wal"))
+    );
+    assert!(restored.get_manual_wal_flush());
+    assert_eq!(
+        restored.get_compression_per_level(),
+        Some(&[DBCompressionType::None, DBCompressionType::Zstd][..])
+    );
+    assert_eq!(
+        restored.get_max_bytes_for_level_multiplier_additional(),
+        Some(&[1, 2, 3][..])
+    );
+    assert!(opts.diff(&restored).is_empty());
+
+    assert!(Options::from_options_string("max_open_files=1;no_such_option=2").is_err());
+    assert!(Options::from_options_string("max_open_files=many").is_err());
+    assert!(Options::from_options_string("merge_operator=unregistered").is_err());
+    assert!(Options::from_options_string("block_based_table_factory={no_such_option=1}").is_err());
+}
+
+#[test]
+fn test_options_diff() {
+    let mut left = Options::default();
+    left.set_max_write_buffer_number(4);
+    left.set_merge_operator_associative("concat", concat_merge);
+    let mut right = Options::default();
+    right.set_max_write_buffer_number(6);
+
+    let diff = left.diff(&right);
+    assert_eq!(
+        diff,
+        vec![
+            OptionDiff {
+                name: "max_write_buffer_number".to_owned(),
+                left: "4".to_owned(),
+                right: "6".to_owned(),
+            },
+            OptionDiff {
+                name: "merge_operator".to_owned(),
+                left: "concat".to_owned(),
+                right: "nullptr".to_owned(),
+            },
+        ]
+    );
+
+    // Options set through setters the C API has no getter for are compared too.
+    let mut left = Options::default();
+    left.set_compression_per_level(&[DBCompressionType::Lz4]);
+    let mut right = Options::default();
+    right.set_compression_per_level(&[DBCompressionType::Zstd]);
+    right.set_dump_malloc_stats(true);
+    assert_eq!(
+        left.diff(&right),
+        vec![
+            OptionDiff {
+                name: "dump_malloc_stats".to_owned(),
+                left: "false".to_owned(),
+                right: "true".to_owned(),
+            },
+            OptionDiff {
+                name: "compression_per_level".to_owned(),
+                left: "kLZ4Compression".to_owned(),
+                right: "kZSTD".to_owned(),
+            },
+        ]
+    );
+
+    let mut block_opts = BlockBasedOptions::default();
+    block_opts.set_block_size(8 << 10);
+    left.set_block_based_table_factory(&block_opts);
+    block_opts.set_block_size(16 << 10);
+    block_opts.set_format_version(5);
+    right.set_block_based_table_factory(&block_opts);
+    let table_diff: Vec<OptionDiff> = left
+        .diff(&right)
+        .into_iter()
+        .filter(|diff| diff.name.starts_with("block_based_table_factory."))
+        .collect();
+    assert_eq!(
+        table_diff,
+        vec![
+            OptionDiff {
+                name: "block_based_table_factory.block_size".to_owned(),
+                left: "8192".to_owned(),
+                right: "16384".to_owned(),
+            },
+            OptionDiff {
+                name: "block_based_table_factory.format_version".to_owned(),
+                left: "".to_owned(),
+                right: "5".to_owned(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_shared_rate_limiter() {
+    let path_a = DBPath::new("_rust_rocksdb_test_shared_rate_limiter_a");
+    let path_b = DBPath::new("_rust_rocksdb_test_shared_rate_limiter_b");
+    let limiter = RateLimiter::new(1 << 20, 100 * 1000, 10).unwrap();
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_rate_limiter(&limiter);
+    let db_a = DB::open(&opts, &path_a).unwrap();
+    let db_b = DB::open(&opts, &path_b).unwrap();
+    // The databases keep the limiter alive.
+    drop(limiter);
+
+    db_a.put(b"k", b"a").unwrap();
+    db_b.put(b"k", b"b").unwrap();
+    db_a.flush().unwrap();
+    db_b.flush().unwrap();
+    assert_eq!(db_a.get(b"k").unwrap().unwrap(), b"a");
+    assert_eq!(db_b.get(b"k").unwrap().unwrap(), b"b");
+}
+
+#[test]
+fn test_partitioned_filter_policy() {
+    let path = DBPath::new("_rust_rocksdb_test_partitioned_filter_policy");
+    let mut block_opts = BlockBasedOptions::default();
+    block_opts.set_filter_policy(FilterPolicy::Bloom { bits_per_key: 10 });
+    block_opts.set_partition_filters(true);
+    block_opts.set_index_type(BlockBasedIndexType::TwoLevelIndexSearch);
+    block_opts.set_whole_key_filtering(true);
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_block_based_table_factory(&block_opts);
+
+    let db = DB::open(&opts, &path).unwrap();
+    for i in 0..1000 {
+        db.put(format!("key{}", i), b"value").unwrap();
+    }
+    db.flush().unwrap();
+    assert_eq!(db.get(b"key10").unwrap().unwrap(), b"value");
+    assert!(db.get(b"missing").unwrap().is_none());
+    // Memory of index and filter blocks held outside of the block cache
+    assert!(db
+        .property_int_value("rocksdb.estimate-table-readers-mem")
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn test_bottommost_compression() {
+    let path = DBPath::new("_rust_rocksdb_test_bottommost_compression");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    assert_eq!(opts.get_bottommost_compression_type(), None);
+    opts.set_compression_per_level(&[
+        DBCompressionType::None,
+        DBCompressionType::Lz4,
+        DBCompressionType::Lz4,
+    ]);
+    opts.set_bottommost_compression_type(DBCompressionType::Zstd);
+    opts.set_bottommost_compression_options(-14, 3, 0, 4096, true);
+    opts.set_bottommost_zstd_max_train_bytes(4096 * 100, true);
+    opts.set_bottommost_max_dict_buffer_bytes(1 << 20, true);
+    opts.set_max_dict_buffer_bytes(1 << 20);
+    opts.set_sample_for_compression(5).unwrap();
+    assert_eq!(opts.get_sample_for_compression(), Some(5));
+    assert_eq!(
+        opts.get_bottommost_compression_type(),
+        Some(DBCompressionType::Zstd)
+    );
+
+    let db = DB::open(&opts, &path).unwrap();
+    db.put(b"k", b"v").unwrap();
+    db.flush().unwrap();
+    db.compact_range(None::<&[u8]>, None::<&[u8]>);
+    assert_eq!(db.get(b"k").unwrap().unwrap(), b"v");
+    assert!(db.set_options(&[("sample_for_compression", "10")]).is_ok());
+}
@@ -0,0 +1,136 @@
+// Copyright 2021 Tyler Neely
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod util;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+use pretty_assertions::assert_eq;
+
+use rocksdb::{
+    secondary::{CatchUpOptions, ManagedSecondary},
+    DBRead, DBWithThreadMode, MultiThreaded, Options, DB,
+};
+use util::DBPath;
+
+#[test]
+fn test_managed_secondary_background_catch_up() {
+    let primary_path = DBPath::new("_rust_rocksdb_test_managed_secondary_background_primary");
+    let secondary_path = DBPath::new("_rust_rocksdb_test_managed_secondary_background_secondary");
+
+    let primary = DB::open_default(&primary_path).unwrap();
+    primary.put(b"key1", b"value1").unwrap();
+
+    let mut opts = Options::default();
+    opts.set_max_open_files(-1);
+    let mut catch_up_opts = CatchUpOptions::default();
+    catch_up_opts.set_interval(Duration::from_millis(10));
+    let secondary =
+        ManagedSecondary::open(&opts, &catch_up_opts, &primary_path, &secondary_path).unwrap();
+    assert_eq!(secondary.last_sequence_number(), 1);
+
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+    secondary.subscribe(move |event| {
+        let _ = tx.lock().unwrap().send(event.sequence_number);
+    });
+
+    primary.put(b"key1", b"value2").unwrap();
+    primary.flush_wal(true).unwrap();
+    while rx.recv_timeout(Duration::from_secs(10)).unwrap() < 2 {}
+
+    assert_eq!(secondary.last_sequence_number(), 2);
+    assert!(secondary.last_error().is_none());
+    assert_eq!(
+        secondary.db().get(b"key1").unwrap().unwrap(),
+        b"value2".to_vec()
+    );
+}
+
+#[test]
+fn test_managed_secondary_discovers_column_families() {
+    let primary_path = DBPath::new("_rust_rocksdb_test_managed_secondary_cfs_primary");
+    let secondary_path = DBPath::new("_rust_rocksdb_test_managed_secondary_cfs_secondary");
+
+    // Multi-threaded so column families can be created through a shared reference.
+    let primary = DBWithThreadMode::<MultiThreaded>::open_default(&primary_path).unwrap();
+
+    let mut opts = Options::default();
+    opts.set_max_open_files(-1);
+    let mut catch_up_opts = CatchUpOptions::default();
+    catch_up_opts.set_interval(Duration::from_secs(3600));
+    let secondary =
+        ManagedSecondary::open(&opts, &catch_up_opts, &primary_path, &secondary_path).unwrap();
+    assert_eq!(secondary.column_families(), vec!["default".to_owned()]);
+
+    primary.create_cf("cf1", &Options::default()).unwrap();
+    let cf1 = primary.cf_handle("cf1").unwrap();
+    primary.put_cf(&cf1, b"key1", b"value1").unwrap();
+
+    let event = secondary.catch_up().unwrap();
+    assert_eq!(event.created_column_families, vec!["cf1".to_owned()]);
+    assert!(event.dropped_column_families.is_empty());
+    {
+        let db = secondary.db();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        assert_eq!(
+            db.get_cf(&cf1, b"key1").unwrap().unwrap(),
+            b"value1".to_vec()
+        );
+    }
+
+    primary.drop_cf("cf1").unwrap();
+    let event = secondary.catch_up().unwrap();
+    assert_eq!(event.dropped_column_families, vec!["cf1".to_owned()]);
+    assert_eq!(secondary.column_families(), vec!["default".to_owned()]);
+}
+
+#[test]
+fn test_managed_secondary_subscriber_calls_back() {
+    let primary_path = DBPath::new("_rust_rocksdb_test_managed_secondary_reentrant_primary");
+    let secondary_path = DBPath::new("_rust_rocksdb_test_managed_secondary_reentrant_secondary");
+
+    let primary = DB::open_default(&primary_path).unwrap();
+
+    let mut opts = Options::default();
+    opts.set_max_open_files(-1);
+    let mut catch_up_opts = CatchUpOptions::default();
+    catch_up_opts.set_interval(Duration::from_secs(3600));
+    let secondary = Arc::new(
+        ManagedSecondary::open(&opts, &catch_up_opts, &primary_path, &secondary_path).unwrap(),
+    );
+
+    // The callback catches up again and subscribes once, which would deadlock
+    // if it ran with the locks of the catch-up held.
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+    let handle = Arc::downgrade(&secondary);
+    let called = AtomicBool::new(false);
+    secondary.subscribe(move |_| {
+        if called.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let secondary = handle.upgrade().unwrap();
+        secondary.subscribe(|_| {});
+        let result = secondary.catch_up().map(|event| event.sequence_number);
+        let _ = tx.lock().unwrap().send(result);
+    });
+
+    primary.put(b"key1", b"value1").unwrap();
+    primary.flush_wal(true).unwrap();
+    assert_eq!(secondary.catch_up().unwrap().sequence_number, 1);
+    assert_eq!(rx.try_recv().unwrap().unwrap(), 1);
+}
@@ -16,7 +16,7 @@ mod util;
 
 use pretty_assertions::assert_eq;
 
-use rocksdb::{Error, Options, SstFileWriter, DB};
+use rocksdb::{ColumnFamilyDescriptor, Error, Options, SliceTransform, SstFileWriter, DB};
 use util::DBPath;
 
 #[test]
@@ -36,8 +36,14 @@ fn sst_file_writer_works() {
         writer.put(b"k2", b"v2").unwrap();
 
         writer.delete(b"k3").unwrap();
-        writer.finish().unwrap();
+        let info = writer.finish().unwrap();
         assert!(writer.file_size() > 0);
+        assert_eq!(info.file_path, writer_path);
+        assert_eq!(info.smallest_key.as_deref(), Some(&b"k1"[..]));
+        assert_eq!(info.largest_key.as_deref(), Some(&b"k3"[..]));
+        assert_eq!(info.sequence_number, 0);
+        assert_eq!(info.num_entries, 3);
+        assert_eq!(info.file_size, writer.file_size());
     }
     {
         let db = DB::open_default(&db_path).unwrap();
@@ -50,3 +56,33 @@ fn sst_file_writer_works() {
         assert!(db.get(b"k3").unwrap().is_none());
     }
 }
+
+#[test]
+fn sst_file_writer_for_cf() {
+    let db_path = DBPath::new("_rust_rocksdb_sstfilewritertest_for_cf");
+    let dir = tempfile::Builder::new()
+        .prefix("_rust_rocksdb_sstfilewritertest_for_cf")
+        .tempdir()
+        .expect("Failed to create temporary path for file writer.");
+    let writer_path = dir.path().join("filewriter");
+
+    let mut cf_opts = Options::default();
+    cf_opts.set_prefix_extractor(SliceTransform::create_fixed_prefix(2));
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let cf = ColumnFamilyDescriptor::new("cf1", cf_opts.clone());
+    let db = DB::open_cf_descriptors(&opts, &db_path, vec![cf]).unwrap();
+
+    let mut writer = SstFileWriter::create(&cf_opts);
+    writer.open(&writer_path).unwrap();
+    writer.put(b"p1k1", b"v1").unwrap();
+    writer.put(b"p1k2", b"v2").unwrap();
+    let info = writer.finish().unwrap();
+    assert_eq!(info.num_entries, 2);
+
+    let cf1 = db.cf_handle("cf1").unwrap();
+    db.ingest_external_file_cf(&cf1, vec![&writer_path])
+        .unwrap();
+    assert_eq!(db.get_cf(&cf1, b"p1k1").unwrap().unwrap(), b"v1");
+}
@@ -12,9 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod util;
+
 use pretty_assertions::assert_eq;
 
-use rocksdb::WriteBatch;
+use rocksdb::{Options, WriteBatch, WriteBatchIteratorCf, DB};
+use util::DBPath;
 
 #[test]
 fn test_write_batch_clear() {
@@ -25,3 +28,71 @@ fn test_write_batch_clear() {
     assert_eq!(batch.len(), 0);
     assert!(batch.is_empty());
 }
+
+#[derive(Debug, Default, PartialEq)]
+struct RecordedOps {
+    ops: Vec<String>,
+}
+
+impl WriteBatchIteratorCf for RecordedOps {
+    fn put_cf(&mut self, cf_id: u32, key: &[u8], value: &[u8]) {
+        self.ops
+            .push(format!("put {} {:?} {:?}", cf_id, key, value));
+    }
+    fn merge_cf(&mut self, cf_id: u32, key: &[u8], value: &[u8]) {
+        self.ops
+            .push(format!("merge {} {:?} {:?}", cf_id, key, value));
+    }
+    fn delete_cf(&mut self, cf_id: u32, key: &[u8]) {
+        self.ops.push(format!("delete {} {:?}", cf_id, key));
+    }
+    fn single_delete_cf(&mut self, cf_id: u32, key: &[u8]) {
+        self.ops.push(format!("single_delete {} {:?}", cf_id, key));
+    }
+    fn delete_range_cf(&mut self, cf_id: u32, from: &[u8], to: &[u8]) {
+        self.ops
+            .push(format!("delete_range {} {:?} {:?}", cf_id, from, to));
+    }
+    fn log_data(&mut self, blob: &[u8]) {
+        self.ops.push(format!("log_data {:?}", blob));
+    }
+}
+
+#[test]
+fn test_write_batch_iterate_cf() {
+    let path = DBPath::new("_rust_rocksdb_test_write_batch_iterate_cf");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = DB::open_cf(&opts, &path, &["cf1"]).unwrap();
+    let cf1 = db.cf_handle("cf1").unwrap();
+    let cf1_id = db.cf_id(&cf1).unwrap();
+    assert_ne!(cf1_id, 0);
+    assert_eq!(db.cf_name_by_id(cf1_id).unwrap(), Some("cf1".to_owned()));
+    assert_eq!(db.cf_name_by_id(0).unwrap(), Some("default".to_owned()));
+    assert_eq!(db.cf_name_by_id(cf1_id + 1).unwrap(), None);
+
+    let mut batch = WriteBatch::default();
+    batch.put(b"k1", b"v1");
+    batch.put_cf(&cf1, b"k2", b"v2");
+    batch.merge_cf(&cf1, b"k3", b"v3");
+    batch.delete(b"k4");
+    batch.single_delete_cf(&cf1, b"k5");
+    batch.delete_range_cf(&cf1, b"k6", b"k7");
+    batch.put_log_data(b"blob");
+
+    let mut recorded = RecordedOps::default();
+    batch.iterate_cf(&mut recorded).unwrap();
+    assert_eq!(
+        recorded.ops,
+        vec![
+            format!("put 0 {:?} {:?}", b"k1", b"v1"),
+            format!("put {} {:?} {:?}", cf1_id, b"k2", b"v2"),
+            format!("merge {} {:?} {:?}", cf1_id, b"k3", b"v3"),
+            format!("delete 0 {:?}", b"k4"),
+            format!("single_delete {} {:?}", cf1_id, b"k5"),
+            format!("delete_range {} {:?} {:?}", cf1_id, b"k6", b"k7"),
+            format!("log_data {:?}", b"blob"),
+        ]
+    );
+}